@@ -0,0 +1,28 @@
+use std::env;
+use std::process::Command;
+
+// IMPORTANT: Records how this binary was built so every result can say where it came from.
+// Frame sizes depend on all of these, so numbers without them cannot be compared later.
+fn main() {
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let var = |name: &str| env::var(name).unwrap_or_else(|_| "unknown".to_string());
+    // Flags are separated by 0x1f in CARGO_ENCODED_RUSTFLAGS
+    let rustflags = env::var("CARGO_ENCODED_RUSTFLAGS")
+        .unwrap_or_default()
+        .replace('\u{1f}', " ");
+
+    println!("cargo:rustc-env=BUILD_RUSTC_VERSION={}", rustc_version);
+    println!("cargo:rustc-env=BUILD_TARGET={}", var("TARGET"));
+    println!("cargo:rustc-env=BUILD_PROFILE={}", var("PROFILE"));
+    println!("cargo:rustc-env=BUILD_OPT_LEVEL={}", var("OPT_LEVEL"));
+    println!("cargo:rustc-env=BUILD_RUSTFLAGS={}", rustflags);
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=RUSTFLAGS");
+}
@@ -0,0 +1,162 @@
+use crate::output::{self, Format};
+use crate::payload::{run_boxed, run_pure, Payload};
+use crate::scenarios::{Scenario, ScenarioResult};
+use std::mem::{align_of, size_of};
+
+// IMPORTANT: simple(u128) costs 112 bytes/level vs 80 for u64 - is that the 16 bytes of
+// data, or the 16-byte alignment forcing the frame to be re-aligned and padded? These
+// payloads keep the size fixed and only raise the alignment, so the difference to the
+// align1 payload of the same size (see payload.rs) is pure padding.
+
+#[derive(Clone, Copy)]
+#[repr(align(16))]
+pub struct Align16<const N: usize>([u8; N]);
+
+#[derive(Clone, Copy)]
+#[repr(align(32))]
+pub struct Align32<const N: usize>([u8; N]);
+
+#[derive(Clone, Copy)]
+#[repr(align(64))]
+pub struct Align64<const N: usize>([u8; N]);
+
+impl<const N: usize> Payload for Align16<N> {
+    fn filled(seed: u8) -> Self {
+        Align16([seed; N])
+    }
+}
+
+impl<const N: usize> Payload for Align32<N> {
+    fn filled(seed: u8) -> Self {
+        Align32([seed; N])
+    }
+}
+
+impl<const N: usize> Payload for Align64<N> {
+    fn filled(seed: u8) -> Self {
+        Align64([seed; N])
+    }
+}
+
+// === SIMD-sized values ===
+impl Payload for u128 {
+    fn filled(seed: u8) -> Self {
+        seed as u128
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+impl Payload for std::arch::x86_64::__m128i {
+    fn filled(seed: u8) -> Self {
+        // SAFETY: SSE2 is part of the x86_64 baseline, so the intrinsic is always available
+        unsafe { std::arch::x86_64::_mm_set1_epi8(seed as i8) }
+    }
+}
+
+pub struct AlignedType {
+    pub label: &'static str,
+    pub size: usize,
+    pub align: usize,
+}
+
+macro_rules! aligned_types {
+    ($($label:literal => $ty:ty),* $(,)?) => {
+        pub const TYPES: &[AlignedType] = &[$(
+            AlignedType {
+                label: $label,
+                size: size_of::<$ty>(),
+                align: align_of::<$ty>(),
+            },
+        )*];
+
+        pub const SCENARIOS: &[Scenario] = &[$(
+            Scenario {
+                name: concat!("align-pure(", $label, ")"),
+                run: run_pure::<$ty>,
+                iterative: false,
+                requires_unwind: false,
+            },
+            Scenario {
+                name: concat!("align-boxed(", $label, ")"),
+                run: run_boxed::<$ty>,
+                iterative: false,
+                requires_unwind: false,
+            },
+        )*];
+    };
+}
+
+#[cfg(target_arch = "x86_64")]
+aligned_types!(
+    "u128" => u128,
+    "__m128i" => std::arch::x86_64::__m128i,
+    "Align16<[u8; 16]>" => Align16<16>,
+    "Align16<[u8; 64]>" => Align16<64>,
+    "Align32<[u8; 32]>" => Align32<32>,
+    "Align32<[u8; 64]>" => Align32<64>,
+    "Align64<[u8; 64]>" => Align64<64>,
+    "Align64<[u8; 128]>" => Align64<128>,
+);
+
+#[cfg(not(target_arch = "x86_64"))]
+aligned_types!(
+    "u128" => u128,
+    "Align16<[u8; 16]>" => Align16<16>,
+    "Align16<[u8; 64]>" => Align16<64>,
+    "Align32<[u8; 32]>" => Align32<32>,
+    "Align32<[u8; 64]>" => Align32<64>,
+    "Align64<[u8; 64]>" => Align64<64>,
+    "Align64<[u8; 128]>" => Align64<128>,
+);
+
+// The align1 payload scenario with the same size, used as the no-padding baseline
+pub fn baseline_name(size: usize) -> String {
+    format!("payload-pure({}B align1)", size)
+}
+
+// === ตาราง padding จาก alignment ===
+// CRITICAL: padding = per-level(aligned type) - per-level([u8; size]) at the same depth
+pub fn render_table(format: Format, results: &[&ScenarioResult]) -> String {
+    let find = |name: &str| results.iter().copied().find(|r| r.name == name);
+    let per_level = |name: &str| {
+        find(name)
+            .and_then(|r| r.report.as_ref())
+            .filter(|report| !report.tail_call_eliminated)
+            .map(|report| report.per_level)
+    };
+    let rows: Vec<Vec<String>> = TYPES
+        .iter()
+        .map(|t| {
+            let pure = format!("align-pure({})", t.label);
+            let boxed = format!("align-boxed({})", t.label);
+            let baseline = baseline_name(t.size);
+            let padding = per_level(&pure)
+                .zip(per_level(&baseline))
+                .map_or("-".to_string(), |(p, b)| format!("{:.2}", p - b));
+            vec![
+                t.label.to_string(),
+                t.size.to_string(),
+                t.align.to_string(),
+                output::per_level_cell(find(&pure)),
+                output::per_level_cell(find(&boxed)),
+                output::per_level_cell(find(&baseline)),
+                padding,
+            ]
+        })
+        .collect();
+    let depth = results.first().map_or(0, |r| r.depth);
+    output::render_grid(
+        format,
+        &format!("Alignment vs per-level cost (depth {})", depth),
+        &[
+            "Type",
+            "Size",
+            "Align",
+            "Pure",
+            "Boxed",
+            "align1 baseline",
+            "Padding",
+        ],
+        &rows,
+    )
+}
@@ -0,0 +1,47 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// IMPORTANT: The system allocator plus a per-thread allocation counter. Each scenario
+// runs on its own thread, so the count is not disturbed by anything else in the process.
+// The const-initialized Cell has no destructor, which keeps it usable inside the allocator.
+// CRITICAL: Only built with --features alloc-count - a counting global allocator taxes every
+// allocation in the process, so the other modes keep the plain system allocator.
+pub struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<u64> = const { Cell::new(0) };
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+// SAFETY: Every call is forwarded unchanged to System, which upholds the GlobalAlloc contract
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        // SAFETY: Same layout the caller passed us
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        // SAFETY: Same layout the caller passed us
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: ptr was allocated by System with this layout
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // Growing an existing block is not a new allocation
+        // SAFETY: ptr was allocated by System with this layout
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+// Allocations made by the current thread so far
+pub fn allocations() -> u64 {
+    ALLOCATIONS.with(Cell::get)
+}
@@ -0,0 +1,248 @@
+#[cfg(feature = "alloc-count")]
+use crate::alloc_count;
+use crate::output::{self, Format};
+use crate::scenarios::{measure, Measurement, Scenario, ScenarioResult};
+use stacker::remaining_stack;
+use std::future::Future;
+use std::hint::black_box;
+use std::mem::size_of_val;
+use std::pin::{pin, Pin};
+use std::task::{Context, Poll, Waker};
+
+// IMPORTANT: A recursive async fn has infinite size unless each level is boxed - exactly the
+// trade-off this project studies. The futures live on the heap, but polling the outer one
+// polls every level below it in nested calls, so the STACK still grows with depth while
+// polling. The samples are taken inside poll, so bytes/level is stack used while polling.
+
+// === executor เล็กๆ (thread เดียว, ไม่มี I/O) ===
+// CRITICAL: Every future here wakes itself before returning Pending, so a busy loop with a
+// no-op waker is a complete executor
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(value) = future.as_mut().poll(&mut cx) {
+            return value;
+        }
+    }
+}
+
+// Returns Pending once, so the next poll has to walk down through every level again
+struct YieldOnce(bool);
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+// === fn ที่คืน Pin<Box<dyn Future>> ===
+pub fn async_boxed_dyn_tracked<'a>(
+    n: u64,
+    stack_info: &'a mut Vec<usize>,
+) -> Pin<Box<dyn Future<Output = ()> + 'a>> {
+    Box::pin(async move {
+        if let Some(rem) = remaining_stack() {
+            stack_info.push(rem);
+        }
+        if n > 0 {
+            async_boxed_dyn_tracked(n - 1, stack_info).await;
+        }
+    })
+}
+
+// === async fn + Box::pin ที่จุด recursion ===
+pub async fn async_fn_tracked(n: u64, stack_info: &mut Vec<usize>) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n > 0 {
+        Box::pin(async_fn_tracked(n - 1, stack_info)).await;
+    }
+}
+
+// === เหมือนข้างบน แต่ leaf yield หนึ่งครั้ง ===
+pub async fn async_yield_tracked(n: u64, stack_info: &mut Vec<usize>) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n > 0 {
+        Box::pin(async_yield_tracked(n - 1, stack_info)).await;
+    } else {
+        YieldOnce(false).await;
+    }
+}
+
+fn drive_boxed_dyn(n: u64, stack_info: &mut Vec<usize>) {
+    block_on(async_boxed_dyn_tracked(n, stack_info))
+}
+
+fn drive_async_fn(n: u64, stack_info: &mut Vec<usize>) {
+    block_on(async_fn_tracked(n, stack_info))
+}
+
+fn drive_yield(n: u64, stack_info: &mut Vec<usize>) {
+    block_on(async_yield_tracked(n, stack_info))
+}
+
+// size_of_val through the Box reads the concrete size from the vtable
+fn boxed_dyn_size() -> usize {
+    size_of_val(&*async_boxed_dyn_tracked(1, &mut Vec::new()))
+}
+
+fn async_fn_size() -> usize {
+    size_of_val(&async_fn_tracked(1, &mut Vec::new()))
+}
+
+fn yield_size() -> usize {
+    size_of_val(&async_yield_tracked(1, &mut Vec::new()))
+}
+
+pub struct AsyncFamily {
+    pub name: &'static str,
+    pub drive: fn(u64, &mut Vec<usize>),
+    pub future_size: fn() -> usize, // One level's future - the heap block behind each Box
+}
+
+pub const FAMILIES: &[AsyncFamily] = &[
+    AsyncFamily {
+        name: "async(Pin<Box<dyn Future>>)",
+        drive: drive_boxed_dyn,
+        future_size: boxed_dyn_size,
+    },
+    AsyncFamily {
+        name: "async(async fn + Box::pin)",
+        drive: drive_async_fn,
+        future_size: async_fn_size,
+    },
+    AsyncFamily {
+        name: "async(yield at leaf)",
+        drive: drive_yield,
+        future_size: yield_size,
+    },
+];
+
+fn run_family(n: u64, drive: fn(u64, &mut Vec<usize>)) -> Measurement {
+    measure(n, || (), |_, stack_info| drive(n, stack_info))
+}
+
+pub const SCENARIOS: &[Scenario] = &[
+    Scenario {
+        name: "async(Pin<Box<dyn Future>>)",
+        run: |n| run_family(n, drive_boxed_dyn),
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "async(async fn + Box::pin)",
+        run: |n| run_family(n, drive_async_fn),
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "async(yield at leaf)",
+        run: |n| run_family(n, drive_yield),
+        iterative: false,
+        requires_unwind: false,
+    },
+];
+
+// Synchronous counterparts shown next to the async rows
+pub const SYNC_BASELINES: &[&str] = &["simple(u64)", "boxed(u64)"];
+
+pub struct FutureProfile {
+    pub future_size: usize,
+    pub allocs_per_level: Option<f64>, // None without the alloc-count feature
+}
+
+#[cfg(feature = "alloc-count")]
+fn count_allocations(f: impl FnOnce()) -> Option<u64> {
+    let before = alloc_count::allocations();
+    f();
+    Some(alloc_count::allocations() - before)
+}
+
+#[cfg(not(feature = "alloc-count"))]
+fn count_allocations(f: impl FnOnce()) -> Option<u64> {
+    f();
+    None
+}
+
+pub fn profile(family: &AsyncFamily, n: u64) -> FutureProfile {
+    let future_size = (family.future_size)();
+    let mut stack_info = Vec::with_capacity(n as usize + 2);
+    let allocs = count_allocations(|| (family.drive)(n, &mut stack_info));
+    black_box(&stack_info);
+    FutureProfile {
+        future_size,
+        allocs_per_level: allocs.map(|allocs| allocs as f64 / (n + 1) as f64),
+    }
+}
+
+// === ตารางเทียบ async กับ sync ===
+// CRITICAL: The futures are profiled in a separate run (on a thread with the same stack
+// size) because the generic scenario results carry no allocation counts
+pub fn render_table(format: Format, results: &[&ScenarioResult], stack_size: usize) -> String {
+    let rows: Vec<Vec<String>> = SYNC_BASELINES
+        .iter()
+        .copied()
+        .chain(SCENARIOS.iter().map(|s| s.name))
+        .filter_map(|name| {
+            let result = results.iter().copied().find(|r| r.name == name)?;
+            let depth = result.depth;
+            let family = FAMILIES.iter().find(|f| f.name == name);
+            let profile = family.zip(result.report.as_ref()).and_then(|(family, _)| {
+                std::thread::Builder::new()
+                    .stack_size(stack_size)
+                    .spawn(move || profile(family, depth))
+                    .ok()
+                    .and_then(|handle| handle.join().ok())
+            });
+            let max_depth = result.report.as_ref().map_or("-".to_string(), |r| {
+                // Only the eval runs as a loop - build and drop still cost heap and time
+                if r.tail_call_eliminated {
+                    "eval unbounded".to_string()
+                } else {
+                    format!("~{}", (stack_size as f64 / r.per_level) as u64)
+                }
+            });
+            Some(vec![
+                name.to_string(),
+                output::per_level_cell(Some(result)),
+                profile
+                    .as_ref()
+                    .map_or("-".to_string(), |p| p.future_size.to_string()),
+                profile
+                    .as_ref()
+                    .and_then(|p| p.allocs_per_level)
+                    .map_or("-".to_string(), |allocs| format!("{:.2}", allocs)),
+                max_depth,
+                result
+                    .timing
+                    .as_ref()
+                    .map_or("-".to_string(), |t| format!("{:.1}", t.eval_ns_per_level)),
+            ])
+        })
+        .collect();
+    let depth = results.first().map_or(0, |r| r.depth);
+    output::render_grid(
+        format,
+        &format!("Async recursion vs sync (depth {})", depth),
+        &[
+            "Scenario",
+            "B/lvl (polling)",
+            "Future size",
+            "Allocs/lvl",
+            "Max depth (est.)",
+            "Eval ns/lvl",
+        ],
+        &rows,
+    )
+}
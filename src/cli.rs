@@ -0,0 +1,444 @@
+use crate::alignment;
+use crate::async_rec;
+use crate::closure;
+use crate::dyn_node;
+use crate::factorial;
+use crate::fold;
+use crate::matrix;
+use crate::metadata;
+use crate::mutual;
+use crate::output::{self, Format};
+use crate::passing;
+use crate::payload;
+#[cfg(feature = "rayon")]
+use crate::rayon_pool;
+use crate::scenarios::{self, RunConfig, Scenario, ScenarioResult};
+use crate::sret;
+#[cfg(feature = "tokio")]
+use crate::tokio_rt;
+use crate::tree;
+use crate::unwind;
+#[cfg(any(feature = "tokio", feature = "rayon"))]
+use crate::workers::{self, WorkerContext};
+
+// === ตัวเลือก command line ===
+// IMPORTANT: No arguments = the original demo in main(). Any argument switches to the
+// scenario runner, which measures every registered scenario at one depth.
+const USAGE: &str = "\
+usage: test_fact [MODE] [OPTIONS]
+
+modes:
+  --scenarios            run every registered scenario (default)
+  --matrix               rebuild and run the scenarios under each build profile
+  --payload-sweep        run the payload scenarios and print bytes/level vs payload size
+  --alignment            run the over-aligned payloads and print the padding they cause
+  --passing              compare passing a struct by value, &T, Box<T> and &mut T
+  --sret                 compare returning large values, Result<u64, LargeError> and Option<[u64; N]>
+  --unwind               panic at the leaf, catch at the top: unwinder stack and unwind time
+  --mutual               mutual recursion (even/odd, A->B->C): frame of each function in the cycle
+  --tree                 binary and n-ary trees: max depth vs node count (--depth = node count)
+  --dyn                  Box<dyn Node> vtable recursion vs the enum version
+  --fold                 recursive eval vs a stack-safe fold that computes n! and the concatenation
+  --factorial            counting down vs computing n! (checked u8/u64/u128, BigUint) and where each overflows
+  --closure              recursion through fn pointers, &dyn Fn, &mut dyn FnMut, Box<dyn Fn>
+                         and closures capturing --struct-size bytes
+  --async                Box::pin async recursion on a local executor vs the sync versions
+                         (allocations per level need --features alloc-count)
+  --tokio                run the scenarios on tokio worker and spawn_blocking threads and
+                         report max safe depth there (needs --features tokio)
+  --rayon                run the scenarios on a rayon pool, alone and as several copies of the
+                         same scenario at once, and report max safe depth there
+                         (needs --features rayon)
+
+options:
+  --format FMT           text (default), csv, json or markdown
+  --depth N[,N...]       recursion depth(s) for each scenario (default 10000)
+  --stack-size BYTES     stack size of the measuring thread (default 8388608)
+  --filter TEXT          only run scenarios whose name contains TEXT
+  --repeat N             measured runs per scenario, reported as statistics (default 1)
+  --warmup N             discarded runs before measuring (default 0)
+  --spread-threshold PCT flag scenarios whose runs disagree by more than PCT% (default 10)
+  --profiles A,B,...     matrix profiles to build (default: all)
+  --worker-stack-size BYTES
+                         thread stack size for --tokio and --rayon (default 2097152)
+  --struct-size N[,N...] struct sizes for --passing and --closure (8 ... 2048) and --sret
+                         (8 ... 1024; default: all)
+  --help                 show this message";
+
+#[derive(PartialEq, Eq)]
+enum Mode {
+    Scenarios,
+    Matrix,
+    PayloadSweep,
+    Alignment,
+    Passing,
+    Sret,
+    Unwind,
+    Mutual,
+    Tree,
+    Dyn,
+    Fold,
+    Factorial,
+    Closure,
+    Async,
+    Tokio,
+    Rayon,
+}
+
+struct Options {
+    mode: Mode,
+    format: Format,
+    depths: Vec<u64>,
+    run: RunConfig,
+    filter: Option<String>,
+    profiles: Option<String>,
+    struct_sizes: Option<Vec<usize>>,
+    #[cfg(any(feature = "tokio", feature = "rayon"))]
+    worker_stack_size: usize,
+}
+
+fn value<'a>(args: &mut impl Iterator<Item = &'a String>, flag: &str) -> Result<&'a str, String> {
+    args.next()
+        .map(|s| s.as_str())
+        .ok_or_else(|| format!("{} needs a value", flag))
+}
+
+fn number<T: std::str::FromStr>(s: &str, flag: &str) -> Result<T, String> {
+    s.parse()
+        .map_err(|_| format!("{} expects a number, got '{}'", flag, s))
+}
+
+fn parse(args: &[String]) -> Result<Options, String> {
+    let mut opts = Options {
+        mode: Mode::Scenarios,
+        format: Format::Text,
+        depths: vec![10_000],
+        run: RunConfig {
+            stack_size: 8 * 1024 * 1024,
+            repeat: 1,
+            warmup: 0,
+            spread_threshold: 0.10,
+        },
+        filter: None,
+        profiles: None,
+        struct_sizes: None,
+        #[cfg(any(feature = "tokio", feature = "rayon"))]
+        worker_stack_size: workers::DEFAULT_WORKER_STACK,
+    };
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--scenarios" => opts.mode = Mode::Scenarios,
+            "--matrix" => opts.mode = Mode::Matrix,
+            "--payload-sweep" => opts.mode = Mode::PayloadSweep,
+            "--alignment" => opts.mode = Mode::Alignment,
+            "--passing" => opts.mode = Mode::Passing,
+            "--sret" => opts.mode = Mode::Sret,
+            "--unwind" => opts.mode = Mode::Unwind,
+            "--mutual" => opts.mode = Mode::Mutual,
+            "--tree" => opts.mode = Mode::Tree,
+            "--dyn" => opts.mode = Mode::Dyn,
+            "--fold" => opts.mode = Mode::Fold,
+            "--factorial" => opts.mode = Mode::Factorial,
+            "--closure" => opts.mode = Mode::Closure,
+            "--async" => opts.mode = Mode::Async,
+            "--tokio" => opts.mode = Mode::Tokio,
+            "--rayon" => opts.mode = Mode::Rayon,
+            #[cfg(any(feature = "tokio", feature = "rayon"))]
+            "--worker-stack-size" => opts.worker_stack_size = number(value(&mut iter, arg)?, arg)?,
+            "--struct-size" => {
+                opts.struct_sizes = Some(
+                    value(&mut iter, arg)?
+                        .split(',')
+                        .map(|s| number(s, arg))
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            "--format" => opts.format = value(&mut iter, arg)?.parse()?,
+            "--depth" => {
+                opts.depths = value(&mut iter, arg)?
+                    .split(',')
+                    .map(|d| number(d, arg))
+                    .collect::<Result<_, _>>()?;
+                // Depth 0 is a single frame - nothing to take a per-level difference from
+                if opts.depths.contains(&0) {
+                    return Err(format!("{} must be at least 1", arg));
+                }
+            }
+            "--stack-size" => opts.run.stack_size = number(value(&mut iter, arg)?, arg)?,
+            "--repeat" => opts.run.repeat = number(value(&mut iter, arg)?, arg)?,
+            "--warmup" => opts.run.warmup = number(value(&mut iter, arg)?, arg)?,
+            "--spread-threshold" => {
+                opts.run.spread_threshold = number::<f64>(value(&mut iter, arg)?, arg)? / 100.0
+            }
+            "--filter" => opts.filter = Some(value(&mut iter, arg)?.to_string()),
+            "--profiles" => opts.profiles = Some(value(&mut iter, arg)?.to_string()),
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            other => return Err(format!("unknown argument '{}'\n\n{}", other, USAGE)),
+        }
+    }
+    Ok(opts)
+}
+
+fn filtered(opts: &Options, registry: Vec<&'static Scenario>) -> Vec<&'static Scenario> {
+    registry
+        .into_iter()
+        .filter(|s| opts.filter.as_deref().is_none_or(|f| s.name.contains(f)))
+        .collect()
+}
+
+fn run_registered(opts: &Options, registry: Vec<&'static Scenario>) -> Vec<ScenarioResult> {
+    let metadata = metadata::collect(Some(opts.run.stack_size));
+    let mut results = Vec::new();
+    for scenario in filtered(opts, registry) {
+        for &depth in &opts.depths {
+            results.push(scenarios::run_scenario(
+                scenario, depth, &opts.run, &metadata,
+            ));
+        }
+    }
+    results
+}
+
+// IMPORTANT: Family modes print the usual results, then the family's own comparison
+// table once per depth (text and markdown only - csv/json already carry every number)
+fn run_family(
+    opts: &Options,
+    registry: Vec<&'static Scenario>,
+    render: impl Fn(Format, &[&ScenarioResult]) -> String,
+) -> Result<(), String> {
+    let results = run_registered(opts, registry);
+    print!("{}", output::render(opts.format, &results));
+    if matches!(opts.format, Format::Text | Format::Markdown) {
+        for &depth in &opts.depths {
+            let at_depth: Vec<_> = results.iter().filter(|r| r.depth == depth).collect();
+            println!();
+            print!("{}", render(opts.format, &at_depth));
+        }
+    }
+    Ok(())
+}
+
+// Picks the scenarios of a const-generic family for the --struct-size selection
+fn sized_family(
+    opts: &Options,
+    sizes: &[usize],
+    variants: &[&str],
+    scenario_name: fn(&str, usize) -> String,
+    scenarios: &'static [Scenario],
+) -> Result<Vec<&'static Scenario>, String> {
+    let selected = opts.struct_sizes.as_deref().unwrap_or(sizes);
+    if let Some(bad) = selected.iter().find(|s| !sizes.contains(s)) {
+        return Err(format!(
+            "--struct-size {} is not compiled in (available: {:?})",
+            bad, sizes
+        ));
+    }
+    let names: Vec<String> = selected
+        .iter()
+        .flat_map(|&b| variants.iter().map(move |v| scenario_name(v, b)))
+        .collect();
+    Ok(scenarios
+        .iter()
+        .filter(|s| names.iter().any(|n| n == s.name))
+        .collect())
+}
+
+// IMPORTANT: Worker modes run every registered scenario in each context, then print one
+// max-safe-depth table per depth
+#[cfg(any(feature = "tokio", feature = "rayon"))]
+fn run_workers(opts: &Options, contexts: &[WorkerContext], title: &str) -> Result<(), String> {
+    let metadata = metadata::collect(Some(opts.worker_stack_size));
+    let registry = filtered(opts, scenarios::registry());
+    let (results, stacks): (Vec<_>, Vec<_>) =
+        workers::run_on(contexts, &registry, &opts.depths, &opts.run, &metadata)
+            .into_iter()
+            .unzip();
+    print!("{}", output::render(opts.format, &results));
+    if matches!(opts.format, Format::Text | Format::Markdown) {
+        for &depth in &opts.depths {
+            let at_depth: Vec<_> = results
+                .iter()
+                .zip(&stacks)
+                .filter(|(r, _)| r.depth == depth)
+                .collect();
+            println!();
+            print!("{}", workers::render_table(opts.format, title, &at_depth));
+        }
+    }
+    Ok(())
+}
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let opts = parse(args)?;
+    let results = match opts.mode {
+        Mode::Scenarios => run_registered(&opts, scenarios::registry()),
+        Mode::PayloadSweep => {
+            let registry = payload::SCENARIOS.iter().collect();
+            return run_family(&opts, registry, payload::render_curve);
+        }
+        Mode::Alignment => {
+            // Same-size align1 payloads are the no-padding baseline
+            let baselines: Vec<String> = alignment::TYPES
+                .iter()
+                .map(|t| alignment::baseline_name(t.size))
+                .collect();
+            let registry = alignment::SCENARIOS
+                .iter()
+                .chain(
+                    payload::SCENARIOS
+                        .iter()
+                        .filter(|s| baselines.iter().any(|b| b == s.name)),
+                )
+                .collect();
+            return run_family(&opts, registry, alignment::render_table);
+        }
+        Mode::Passing => {
+            let registry = sized_family(
+                &opts,
+                passing::SIZES,
+                passing::CONVENTIONS,
+                passing::scenario_name,
+                passing::SCENARIOS,
+            )?;
+            return run_family(&opts, registry, passing::render_table);
+        }
+        Mode::Sret => {
+            let registry = sized_family(
+                &opts,
+                sret::SIZES,
+                sret::KINDS,
+                sret::scenario_name,
+                sret::SCENARIOS,
+            )?;
+            return run_family(&opts, registry, sret::render_table);
+        }
+        Mode::Unwind => {
+            let registry = unwind::SCENARIOS.iter().collect();
+            let stack_size = opts.run.stack_size;
+            return run_family(&opts, registry, |format, results| {
+                unwind::render_table(format, results, stack_size)
+            });
+        }
+        Mode::Mutual => {
+            let registry = mutual::SCENARIOS.iter().collect();
+            let stack_size = opts.run.stack_size;
+            return run_family(&opts, registry, |format, results| {
+                mutual::render_table(format, results, stack_size)
+            });
+        }
+        Mode::Tree => {
+            let registry = tree::SCENARIOS.iter().collect();
+            return run_family(&opts, registry, tree::render_table);
+        }
+        Mode::Dyn => {
+            let registry = scenarios::SCENARIOS
+                .iter()
+                .chain(dyn_node::SCENARIOS)
+                .filter(|s| {
+                    dyn_node::PAIRS
+                        .iter()
+                        .any(|&(_, e, d)| s.name == e || s.name == d)
+                })
+                .collect();
+            return run_family(&opts, registry, dyn_node::render_table);
+        }
+        Mode::Fold => {
+            let registry = scenarios::registry()
+                .into_iter()
+                .filter(|s| {
+                    fold::PAIRS
+                        .iter()
+                        .any(|&(_, r, f, _)| s.name == r || s.name == f)
+                })
+                .collect();
+            return run_family(&opts, registry, fold::render_table);
+        }
+        Mode::Factorial => {
+            let registry = scenarios::registry()
+                .into_iter()
+                .filter(|s| {
+                    factorial::ROWS
+                        .iter()
+                        .any(|&(_, c, f, _, _)| s.name == c || s.name == f)
+                })
+                .collect();
+            return run_family(&opts, registry, factorial::render_table);
+        }
+        Mode::Closure => {
+            let captured = sized_family(
+                &opts,
+                closure::SIZES,
+                closure::VARIANTS,
+                closure::scenario_name,
+                closure::CAPTURED,
+            )?;
+            let registry = closure::SCENARIOS.iter().chain(captured).collect();
+            return run_family(&opts, registry, closure::render_table);
+        }
+        Mode::Async => {
+            let registry = scenarios::SCENARIOS
+                .iter()
+                .filter(|s| async_rec::SYNC_BASELINES.contains(&s.name))
+                .chain(async_rec::SCENARIOS)
+                .collect();
+            let stack_size = opts.run.stack_size;
+            return run_family(&opts, registry, |format, results| {
+                async_rec::render_table(format, results, stack_size)
+            });
+        }
+        #[cfg(feature = "tokio")]
+        Mode::Tokio => {
+            let contexts = tokio_rt::contexts(opts.worker_stack_size)?;
+            return run_workers(&opts, &contexts, "Max safe depth on tokio threads");
+        }
+        #[cfg(not(feature = "tokio"))]
+        Mode::Tokio => {
+            return Err(
+                "--tokio needs the tokio feature: cargo run --features tokio -- --tokio"
+                    .to_string(),
+            );
+        }
+        #[cfg(feature = "rayon")]
+        Mode::Rayon => {
+            let contexts = rayon_pool::contexts(opts.worker_stack_size)?;
+            return run_workers(&opts, &contexts, "Max safe depth on rayon threads");
+        }
+        #[cfg(not(feature = "rayon"))]
+        Mode::Rayon => {
+            return Err(
+                "--rayon needs the rayon feature: cargo run --features rayon -- --rayon"
+                    .to_string(),
+            );
+        }
+        Mode::Matrix => {
+            let profiles = matrix::select_profiles(opts.profiles.as_deref())?;
+            let mut child_args = vec![
+                "--depth".to_string(),
+                opts.depths
+                    .iter()
+                    .map(u64::to_string)
+                    .collect::<Vec<_>>()
+                    .join(","),
+                "--stack-size".to_string(),
+                opts.run.stack_size.to_string(),
+                "--repeat".to_string(),
+                opts.run.repeat.to_string(),
+                "--warmup".to_string(),
+                opts.run.warmup.to_string(),
+                "--spread-threshold".to_string(),
+                (opts.run.spread_threshold * 100.0).to_string(),
+            ];
+            if let Some(filter) = &opts.filter {
+                child_args.push("--filter".to_string());
+                child_args.push(filter.clone());
+            }
+            matrix::run_matrix(&profiles, &child_args)
+        }
+    };
+    print!("{}", output::render(opts.format, &results));
+    Ok(())
+}
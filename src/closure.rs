@@ -0,0 +1,227 @@
+use crate::output::{self, Format};
+use crate::scenarios::{measure, Measurement, Scenario, ScenarioResult};
+use stacker::remaining_stack;
+use std::hint::black_box;
+
+// IMPORTANT: Same recursion as simple_factorial_tracked_u64, but every level asks a
+// callback for the next argument and passes the callback on to the next level. Only the
+// kind of callback differs: fn pointer, &dyn Fn, &mut dyn FnMut, Box<dyn Fn>, or a closure
+// that captures N bytes of state and is passed BY VALUE like any other generic argument.
+// CRITICAL: Every level reads n after the call returns, so the recursive call is never in
+// tail position - otherwise release turns the cheap callbacks into a loop and shows nothing.
+
+// === เรียกตรง (baseline) ===
+// simple(u64) is a tail call in release, so the baseline is the same non-tail shape
+pub fn direct_tracked(n: u64, stack_info: &mut Vec<usize>) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n > 0 {
+        direct_tracked(decrement(n), stack_info);
+        black_box(n);
+    }
+}
+
+// === fn pointer ===
+fn decrement(n: u64) -> u64 {
+    n - 1
+}
+
+pub fn fn_pointer_tracked(n: u64, stack_info: &mut Vec<usize>, step: fn(u64) -> u64) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n > 0 {
+        fn_pointer_tracked(step(n), stack_info, step);
+        black_box(n);
+    }
+}
+
+// === &dyn Fn ===
+pub fn dyn_fn_tracked(n: u64, stack_info: &mut Vec<usize>, step: &dyn Fn(u64) -> u64) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n > 0 {
+        dyn_fn_tracked(step(n), stack_info, step);
+        black_box(n);
+    }
+}
+
+// === &mut dyn FnMut ===
+pub fn dyn_fn_mut_tracked(n: u64, stack_info: &mut Vec<usize>, step: &mut dyn FnMut(u64) -> u64) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n > 0 {
+        let next = step(n);
+        dyn_fn_mut_tracked(next, stack_info, step);
+        black_box(n);
+    }
+}
+
+// === Box<dyn Fn> (ownership ส่งต่อทุก level) ===
+pub fn boxed_fn_tracked(n: u64, stack_info: &mut Vec<usize>, step: Box<dyn Fn(u64) -> u64>) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n > 0 {
+        let next = step(n);
+        boxed_fn_tracked(next, stack_info, step);
+        black_box(n);
+    }
+}
+
+// === closure ที่ capture state ขนาด N (ส่งแบบ by value) ===
+pub fn captured_tracked<F: Fn(u64) -> u64 + Copy>(n: u64, stack_info: &mut Vec<usize>, step: F) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n > 0 {
+        captured_tracked(step(n), stack_info, black_box(step));
+        black_box(n);
+    }
+}
+
+fn run_direct(n: u64) -> Measurement {
+    measure(n, || (), |_, stack_info| direct_tracked(n, stack_info))
+}
+
+fn run_fn_pointer(n: u64) -> Measurement {
+    // black_box hides which function the pointer targets, so the call stays indirect
+    measure(
+        n,
+        || black_box(decrement as fn(u64) -> u64),
+        |step, stack_info| fn_pointer_tracked(n, stack_info, *step),
+    )
+}
+
+fn run_dyn_fn(n: u64) -> Measurement {
+    measure(
+        n,
+        || black_box(1u64),
+        |by, stack_info| {
+            let by = *by;
+            dyn_fn_tracked(n, stack_info, &move |n| n - by)
+        },
+    )
+}
+
+fn run_dyn_fn_mut(n: u64) -> Measurement {
+    measure(
+        n,
+        || 0u64,
+        |calls, stack_info| {
+            dyn_fn_mut_tracked(n, stack_info, &mut |n| {
+                *calls += 1; // State the callback mutates on every level
+                n - 1
+            })
+        },
+    )
+}
+
+fn run_boxed_fn(n: u64) -> Measurement {
+    measure(
+        n,
+        || black_box(1u64),
+        |by, stack_info| {
+            let by = *by;
+            boxed_fn_tracked(n, stack_info, Box::new(move |n| n - by))
+        },
+    )
+}
+
+fn run_captured<const N: usize>(n: u64) -> Measurement {
+    measure(
+        n,
+        || black_box([1u8; N]),
+        |state, stack_info| {
+            let state = *state;
+            // state[0] is 1, but only at runtime - the whole array stays captured
+            captured_tracked(n, stack_info, move |n| {
+                n - state.first().copied().map_or(1, u64::from)
+            })
+        },
+    )
+}
+
+pub const SCENARIOS: &[Scenario] = &[
+    Scenario {
+        name: "closure(direct call)",
+        run: run_direct,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "closure(fn pointer)",
+        run: run_fn_pointer,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "closure(&dyn Fn)",
+        run: run_dyn_fn,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "closure(&mut dyn FnMut)",
+        run: run_dyn_fn_mut,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "closure(Box<dyn Fn>)",
+        run: run_boxed_fn,
+        iterative: false,
+        requires_unwind: false,
+    },
+];
+
+pub const VARIANTS: &[&str] = &["captured"];
+
+// IMPORTANT: The capture size is a const generic, so --struct-size picks from these
+macro_rules! captured_sizes {
+    ($($bytes:literal),*) => {
+        pub const SIZES: &[usize] = &[$($bytes),*];
+
+        pub const CAPTURED: &[Scenario] = &[$(
+            Scenario {
+                name: concat!("closure(captured ", $bytes, "B)"),
+                run: run_captured::<$bytes>,
+                iterative: false,
+                requires_unwind: false,
+            },
+        )*];
+    };
+}
+
+captured_sizes!(8, 16, 32, 64, 128, 256, 512, 1024, 2048);
+
+pub fn scenario_name(variant: &str, bytes: usize) -> String {
+    format!("closure({} {}B)", variant, bytes)
+}
+
+// === ตารางเทียบ indirection ===
+// CRITICAL: closure(direct call) is the baseline for every row
+pub fn render_table(format: Format, results: &[&ScenarioResult]) -> String {
+    let rows: Vec<Vec<String>> = results
+        .iter()
+        .map(|r| {
+            vec![
+                r.name.clone(),
+                output::per_level_cell(Some(r)),
+                r.timing
+                    .as_ref()
+                    .map_or("-".to_string(), |t| format!("{:.1}", t.eval_ns_per_level)),
+            ]
+        })
+        .collect();
+    let depth = results.first().map_or(0, |r| r.depth);
+    output::render_grid(
+        format,
+        &format!("Recursing through callbacks (depth {})", depth),
+        &["Scenario", "B/lvl", "Eval ns/lvl"],
+        &rows,
+    )
+}
@@ -0,0 +1,474 @@
+use crate::{BoxedFact, BoxedString};
+use stacker::remaining_stack;
+use std::iter::FromIterator;
+use std::mem::ManuallyDrop;
+use std::ptr;
+
+// IMPORTANT: BoxedFact and BoxedString are singly linked lists that always end in Done, so
+// they are never empty. Everything here walks them with a loop - stack use stays flat at
+// any length, unlike eval_boxed_fact_tracked. Order is head first: Next(n, ...) ... Done.
+
+// === แยกหาง / ต่อหาง ===
+// CRITICAL: A node has no empty state to leave behind while one of its fields is moved
+// out, so unlinking and relinking read the fields with ptr::read and write the node straight
+// back. take_rest, append and into_value are the only unsafe code here; drop, IntoIter and
+// Extend are built on them.
+impl<T> BoxedFact<T> {
+    // Unlinks everything after the head and leaves Done(head value) in its place
+    fn take_rest(&mut self) -> Option<Box<BoxedFact<T>>> {
+        let slot: *mut BoxedFact<T> = self;
+        let BoxedFact::Next(value, next) = self else {
+            return None;
+        };
+        // SAFETY: Both fields are read out and the node is overwritten before anything can
+        // panic or look at it, so each field ends up owned exactly once
+        unsafe {
+            let value = ptr::read(value);
+            let next = ptr::read(next);
+            ptr::write(slot, BoxedFact::Done(value));
+            Some(next)
+        }
+    }
+
+    // Links rest after the Done tail - the inverse of take_rest
+    fn append(&mut self, rest: Box<BoxedFact<T>>) {
+        let mut tail = self;
+        while let BoxedFact::Next(_, next) = tail {
+            tail = next;
+        }
+        let slot: *mut BoxedFact<T> = tail;
+        if let BoxedFact::Done(value) = tail {
+            // SAFETY: Same as take_rest - the value is moved into the node written back
+            unsafe {
+                let value = ptr::read(value);
+                ptr::write(slot, BoxedFact::Next(value, rest));
+            }
+        }
+    }
+
+    fn into_value(mut self) -> T {
+        drop(self.take_rest());
+        let node = ManuallyDrop::new(self);
+        match &*node {
+            // SAFETY: node is never dropped, so the value is moved out exactly once
+            BoxedFact::Next(value, _) | BoxedFact::Done(value) => unsafe { ptr::read(value) },
+        }
+    }
+
+    // Head first; None for an empty iterator - there is no empty BoxedFact
+    pub fn from_values(values: impl IntoIterator<Item = T>) -> Option<Self> {
+        let mut values: Vec<T> = values.into_iter().collect();
+        let mut current = BoxedFact::Done(values.pop()?);
+        while let Some(value) = values.pop() {
+            current = BoxedFact::Next(value, Box::new(current));
+        }
+        Some(current)
+    }
+}
+
+// === drop ทีละ node ===
+// KEY: The derived drop recursed once per node and overflowed at depths where a flat or
+// tail-call-eliminated eval fits easily. Each node is unlinked before it is dropped.
+impl<T> Drop for BoxedFact<T> {
+    fn drop(&mut self) {
+        let mut rest = self.take_rest();
+        while let Some(mut node) = rest {
+            rest = node.take_rest();
+        }
+    }
+}
+
+// === BoxedFact: iterator แบบยืม ===
+pub struct Iter<'a, T> {
+    next: Option<&'a BoxedFact<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        match self.next? {
+            BoxedFact::Next(value, next) => {
+                self.next = Some(next);
+                Some(value)
+            }
+            BoxedFact::Done(value) => {
+                self.next = None;
+                Some(value)
+            }
+        }
+    }
+}
+
+// === BoxedFact: iterator แบบ by value ===
+pub struct IntoIter<T> {
+    next: Option<BoxedFact<T>>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let mut node = self.next.take()?;
+        self.next = node.take_rest().map(|rest| *rest);
+        Some(node.into_value())
+    }
+}
+
+impl<T> BoxedFact<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { next: Some(self) }
+    }
+
+    #[allow(dead_code)] // Collection API; the scenarios only walk with iter()
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        false // Always ends in Done
+    }
+
+    // === fold: ยุบทั้ง list ด้วย loop ===
+    // KEY: Head first, the order eval_boxed_fact_tracked visits in. For an associative
+    // combine (sum, product, concatenation) that is the recursion's answer in one frame.
+    pub fn fold<B>(&self, init: B, f: impl FnMut(B, &T) -> B) -> B {
+        self.iter().fold(init, f)
+    }
+
+    pub fn fold_tracked<B>(
+        &self,
+        init: B,
+        mut f: impl FnMut(B, &T) -> B,
+        stack_info: &mut Vec<usize>,
+    ) -> B {
+        let mut acc = init;
+        for value in self {
+            if let Some(rem) = remaining_stack() {
+                stack_info.push(rem); // One sample per node, like the recursion
+            }
+            acc = f(acc, value);
+        }
+        acc
+    }
+}
+
+impl<T> IntoIterator for BoxedFact<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { next: Some(self) }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a BoxedFact<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+// CRITICAL: Panics on an empty iterator - there is no empty BoxedFact. Use from_values
+// when the input may be empty.
+impl<T> FromIterator<T> for BoxedFact<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        BoxedFact::from_values(iter)
+            .expect("BoxedFact needs at least one value (it always ends in Done)")
+    }
+}
+
+// Builds the new items as their own list, then links it after the old tail
+impl<T> Extend<T> for BoxedFact<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        if let Some(rest) = BoxedFact::from_values(iter) {
+            self.append(Box::new(rest));
+        }
+    }
+}
+
+// === BoxedString: ชุดเดียวกัน ===
+impl BoxedString {
+    fn take_rest(&mut self) -> Option<Box<BoxedString>> {
+        let slot: *mut BoxedString = self;
+        let BoxedString::Next(s, next) = self else {
+            return None;
+        };
+        // SAFETY: See BoxedFact::take_rest
+        unsafe {
+            let s = ptr::read(s);
+            let next = ptr::read(next);
+            ptr::write(slot, BoxedString::Done(s));
+            Some(next)
+        }
+    }
+
+    fn append(&mut self, rest: Box<BoxedString>) {
+        let mut tail = self;
+        while let BoxedString::Next(_, next) = tail {
+            tail = next;
+        }
+        let slot: *mut BoxedString = tail;
+        if let BoxedString::Done(s) = tail {
+            // SAFETY: See BoxedFact::take_rest
+            unsafe {
+                let s = ptr::read(s);
+                ptr::write(slot, BoxedString::Next(s, rest));
+            }
+        }
+    }
+
+    fn into_value(mut self) -> String {
+        drop(self.take_rest());
+        let node = ManuallyDrop::new(self);
+        match &*node {
+            // SAFETY: node is never dropped, so the string is moved out exactly once
+            BoxedString::Next(s, _) | BoxedString::Done(s) => unsafe { ptr::read(s) },
+        }
+    }
+
+    pub fn from_parts(parts: impl IntoIterator<Item = String>) -> Option<Self> {
+        let mut parts: Vec<String> = parts.into_iter().collect();
+        let mut current = BoxedString::Done(parts.pop()?);
+        while let Some(part) = parts.pop() {
+            current = BoxedString::Next(part, Box::new(current));
+        }
+        Some(current)
+    }
+}
+
+impl Drop for BoxedString {
+    fn drop(&mut self) {
+        let mut rest = self.take_rest();
+        while let Some(mut node) = rest {
+            rest = node.take_rest();
+        }
+    }
+}
+
+pub struct StringIter<'a> {
+    next: Option<&'a BoxedString>,
+}
+
+impl<'a> Iterator for StringIter<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        match self.next? {
+            BoxedString::Next(s, next) => {
+                self.next = Some(next);
+                Some(s)
+            }
+            BoxedString::Done(s) => {
+                self.next = None;
+                Some(s)
+            }
+        }
+    }
+}
+
+pub struct StringIntoIter {
+    next: Option<BoxedString>,
+}
+
+impl Iterator for StringIntoIter {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        let mut node = self.next.take()?;
+        self.next = node.take_rest().map(|rest| *rest);
+        Some(node.into_value())
+    }
+}
+
+impl BoxedString {
+    pub fn iter(&self) -> StringIter<'_> {
+        StringIter { next: Some(self) }
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    pub fn fold<B>(&self, init: B, f: impl FnMut(B, &str) -> B) -> B {
+        self.iter().fold(init, f)
+    }
+
+    pub fn fold_tracked<B>(
+        &self,
+        init: B,
+        mut f: impl FnMut(B, &str) -> B,
+        stack_info: &mut Vec<usize>,
+    ) -> B {
+        let mut acc = init;
+        for s in self {
+            if let Some(rem) = remaining_stack() {
+                stack_info.push(rem);
+            }
+            acc = f(acc, s);
+        }
+        acc
+    }
+}
+
+impl IntoIterator for BoxedString {
+    type Item = String;
+    type IntoIter = StringIntoIter;
+
+    fn into_iter(self) -> StringIntoIter {
+        StringIntoIter { next: Some(self) }
+    }
+}
+
+impl<'a> IntoIterator for &'a BoxedString {
+    type Item = &'a str;
+    type IntoIter = StringIter<'a>;
+
+    fn into_iter(self) -> StringIter<'a> {
+        self.iter()
+    }
+}
+
+// Panics on an empty iterator, like BoxedFact; from_parts is the fallible path
+impl FromIterator<String> for BoxedString {
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Self {
+        BoxedString::from_parts(iter)
+            .expect("BoxedString needs at least one part (it always ends in Done)")
+    }
+}
+
+impl Extend<String> for BoxedString {
+    fn extend<I: IntoIterator<Item = String>>(&mut self, iter: I) {
+        if let Some(rest) = BoxedString::from_parts(iter) {
+            self.append(Box::new(rest));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(fact: &BoxedFact<u64>) -> Vec<u64> {
+        fact.iter().copied().collect()
+    }
+
+    #[test]
+    fn iter_is_head_first() {
+        let fact = crate::make_boxed_fact_u64(3);
+        assert_eq!(values(&fact), [3, 2, 1, 1]);
+        assert_eq!((&fact).into_iter().count(), fact.len());
+        assert_eq!(fact.len(), 4);
+    }
+
+    #[test]
+    fn from_values_keeps_order_and_rejects_empty() {
+        let fact = BoxedFact::from_values([1u64, 2, 3]).unwrap();
+        assert_eq!(values(&fact), [1, 2, 3]);
+        assert!(BoxedFact::<u64>::from_values([]).is_none());
+        assert!(BoxedString::from_parts([]).is_none());
+    }
+
+    #[test]
+    fn collect_builds_head_first() {
+        let fact: BoxedFact<u64> = (1..=4).collect();
+        assert_eq!(values(&fact), [1, 2, 3, 4]);
+        let strings: BoxedString = ["a", "b"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(strings.iter().collect::<Vec<_>>(), ["a", "b"]);
+        let round_trip: BoxedFact<u64> = fact.into_iter().collect();
+        assert_eq!(values(&round_trip), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one value")]
+    fn collect_panics_on_empty_input() {
+        let _: BoxedFact<u64> = std::iter::empty().collect();
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one part")]
+    fn collect_strings_panics_on_empty_input() {
+        let _: BoxedString = std::iter::empty().collect();
+    }
+
+    #[test]
+    fn extend_appends_after_done() {
+        let mut fact = BoxedFact::Done(1u64);
+        fact.extend([]);
+        fact.extend([2, 3]);
+        fact.extend([4]);
+        assert_eq!(values(&fact), [1, 2, 3, 4]);
+        assert_eq!(fact.into_iter().collect::<Vec<_>>(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn extend_needs_no_default() {
+        struct NoDefault(u8);
+        let mut fact = BoxedFact::Done(NoDefault(1));
+        fact.extend([NoDefault(2)]);
+        assert_eq!(fact.iter().map(|v| v.0).collect::<Vec<_>>(), [1, 2]);
+    }
+
+    #[test]
+    fn strings_round_trip() {
+        let mut strings = BoxedString::from_parts(["a".to_string(), "b".to_string()]).unwrap();
+        strings.extend(["c".to_string()]);
+        assert_eq!(strings.len(), 3);
+        assert_eq!(strings.iter().collect::<String>(), "abc");
+        assert_eq!(strings.into_iter().collect::<Vec<_>>(), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn partly_consumed_into_iter_drops_the_rest() {
+        let mut iter = crate::make_boxed_string(5).into_iter();
+        assert_eq!(iter.next().as_deref(), Some("1-"));
+        drop(iter);
+    }
+
+    #[test]
+    fn fold_is_head_first() {
+        let fact = crate::make_boxed_fact_u64(5);
+        assert_eq!(fact.fold(1u64, |acc, &v| acc * v), 120);
+        let order = fact.fold(Vec::new(), |mut seen, &v| {
+            seen.push(v);
+            seen
+        });
+        assert_eq!(order, [5, 4, 3, 2, 1, 1]);
+        let strings = crate::make_boxed_string(3);
+        assert_eq!(strings.fold(String::new(), |out, s| out + s), "1-2-3-0-");
+    }
+
+    #[test]
+    fn fold_tracked_samples_once_per_node() {
+        let fact = crate::make_boxed_fact_u64(9);
+        let mut stack_info = Vec::new();
+        assert_eq!(
+            fact.fold_tracked(0u64, |sum, &v| sum + v, &mut stack_info),
+            46
+        );
+        assert_eq!(stack_info.len(), fact.len());
+        let strings = crate::make_boxed_string(4);
+        let mut stack_info = Vec::new();
+        let len = strings.fold_tracked(0, |len, s| len + s.len(), &mut stack_info);
+        assert_eq!(len, strings.iter().map(str::len).sum::<usize>());
+        assert_eq!(stack_info.len(), strings.len());
+    }
+
+    // Far deeper than a recursive drop survives on the 2 MiB test thread
+    #[test]
+    fn deep_lists_drop_without_recursion() {
+        drop(crate::make_boxed_fact_u64(1_000_000));
+        drop(crate::make_boxed_string(1_000_000));
+        let sum: u64 = crate::make_boxed_fact_u64(1_000_000).into_iter().sum();
+        assert_eq!(sum, 500_000_500_001);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::output::{self, Format};
+use crate::scenarios::{measure, Measurement, Scenario, ScenarioResult};
+use crate::{make_boxed_fact_u64, BoxedFact};
+use stacker::remaining_stack;
+use std::hint::black_box;
+use std::mem;
+
+// IMPORTANT: eval_boxed_fact_tracked is statically dispatched over an enum, so the compiler
+// sees the whole recursion. Here every node is a Box<dyn Node> and each level reaches the
+// next through a vtable call. Same chain, same work - only the dispatch differs.
+
+pub trait Node {
+    fn eval(&self, stack_info: &mut Vec<usize>);
+    fn count(&self, stack_info: &mut Vec<usize>) -> u64;
+
+    // Unlinks the rest of the chain so NextNode's Drop can free it in a loop
+    fn take_next(&mut self) -> Option<Box<dyn Node>> {
+        None
+    }
+}
+
+pub struct NextNode<T> {
+    #[allow(dead_code)] // Payload only, like the value in BoxedFact::Next
+    pub value: T,
+    pub next: Box<dyn Node>,
+}
+
+pub struct DoneNode<T> {
+    #[allow(dead_code)]
+    pub value: T,
+}
+
+impl<T> Node for NextNode<T> {
+    fn eval(&self, stack_info: &mut Vec<usize>) {
+        if let Some(rem) = remaining_stack() {
+            stack_info.push(rem);
+        }
+        self.next.eval(stack_info) // vtable call
+    }
+
+    fn count(&self, stack_info: &mut Vec<usize>) -> u64 {
+        if let Some(rem) = remaining_stack() {
+            stack_info.push(rem);
+        }
+        1 + self.next.count(stack_info)
+    }
+
+    fn take_next(&mut self) -> Option<Box<dyn Node>> {
+        // A zero-sized DoneNode does not allocate, so the placeholder costs nothing
+        Some(mem::replace(
+            &mut self.next,
+            Box::new(DoneNode { value: () }),
+        ))
+    }
+}
+
+// === drop ทีละ node ===
+// CRITICAL: The derived drop recursed once per node - dyn-node(u64) is a tail call in
+// release, so it passed check_fits at depths where dropping the chain aborted the process
+impl<T> Drop for NextNode<T> {
+    fn drop(&mut self) {
+        let mut next = self.take_next();
+        while let Some(mut node) = next {
+            next = node.take_next();
+        }
+    }
+}
+
+impl<T> Node for DoneNode<T> {
+    fn eval(&self, stack_info: &mut Vec<usize>) {
+        if let Some(rem) = remaining_stack() {
+            stack_info.push(rem);
+        }
+    }
+
+    fn count(&self, stack_info: &mut Vec<usize>) -> u64 {
+        if let Some(rem) = remaining_stack() {
+            stack_info.push(rem);
+        }
+        0
+    }
+}
+
+pub fn make_dyn_fact_u64(n: u64) -> Box<dyn Node> {
+    // IMPORTANT: Iterative creation, same as make_boxed_fact_u64
+    let mut current: Box<dyn Node> = Box::new(DoneNode { value: 1u64 });
+    for i in 1..=n {
+        current = Box::new(NextNode {
+            value: i,
+            next: current,
+        });
+    }
+    current
+}
+
+// === enum เวอร์ชันที่ไม่ใช่ tail call (คู่เทียบของ count) ===
+pub fn count_boxed_fact_tracked<T>(f: &BoxedFact<T>, stack_info: &mut Vec<usize>) -> u64 {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    match f {
+        BoxedFact::Next(_, next) => 1 + count_boxed_fact_tracked(next, stack_info),
+        BoxedFact::Done(_) => 0,
+    }
+}
+
+fn run_dyn(n: u64) -> Measurement {
+    measure(
+        n,
+        || make_dyn_fact_u64(n),
+        |node, stack_info| node.eval(stack_info),
+    )
+}
+
+fn run_dyn_count(n: u64) -> Measurement {
+    measure(
+        n,
+        || make_dyn_fact_u64(n),
+        |node, stack_info| {
+            black_box(node.count(stack_info));
+        },
+    )
+}
+
+fn run_enum_count(n: u64) -> Measurement {
+    measure(
+        n,
+        || make_boxed_fact_u64(n),
+        |fact, stack_info| {
+            black_box(count_boxed_fact_tracked(fact, stack_info));
+        },
+    )
+}
+
+pub const SCENARIOS: &[Scenario] = &[
+    Scenario {
+        name: "dyn-node(u64)",
+        run: run_dyn,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "dyn-node-count(u64)",
+        run: run_dyn_count,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "enum-node-count(u64)",
+        run: run_enum_count,
+        iterative: false,
+        requires_unwind: false,
+    },
+];
+
+// (shape, enum scenario, dyn scenario) - the tail-call pair reuses the original boxed(u64)
+pub const PAIRS: &[(&str, &str, &str)] = &[
+    ("tail call", "boxed(u64)", "dyn-node(u64)"),
+    ("1 + next", "enum-node-count(u64)", "dyn-node-count(u64)"),
+];
+
+// === ตารางเทียบ enum กับ dyn ===
+// CRITICAL: A vtable call cannot be inlined, so a gap between the columns is the dispatch;
+// "tail-call" on one side only means the optimizer saw through the enum but not the vtable
+pub fn render_table(format: Format, results: &[&ScenarioResult]) -> String {
+    let find = |name: &str| results.iter().copied().find(|r| r.name == name);
+    let eval = |name: &str| {
+        find(name)
+            .and_then(|r| r.timing.as_ref())
+            .map_or("-".to_string(), |t| format!("{:.1}", t.eval_ns_per_level))
+    };
+    let rows: Vec<Vec<String>> = PAIRS
+        .iter()
+        .map(|&(shape, enum_name, dyn_name)| {
+            vec![
+                shape.to_string(),
+                output::per_level_cell(find(enum_name)),
+                output::per_level_cell(find(dyn_name)),
+                eval(enum_name),
+                eval(dyn_name),
+            ]
+        })
+        .collect();
+    let depth = results.first().map_or(0, |r| r.depth);
+    output::render_grid(
+        format,
+        &format!("Enum vs Box<dyn Node> dispatch (depth {})", depth),
+        &[
+            "Recursion",
+            "enum B/lvl",
+            "dyn B/lvl",
+            "enum ns/lvl",
+            "dyn ns/lvl",
+        ],
+        &rows,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn deep_chains_drop_without_recursion() {
+        drop(super::make_dyn_fact_u64(1_000_000));
+    }
+}
@@ -0,0 +1,440 @@
+use crate::output::{self, Format};
+use crate::scenarios::{measure, Measurement, Scenario, ScenarioResult};
+use crate::{make_boxed_fact_u128, make_boxed_fact_u64, make_boxed_fact_u8, BoxedFact};
+use stacker::remaining_stack;
+use std::fmt;
+use std::hint::black_box;
+use std::ops::Sub;
+
+// IMPORTANT: simple_factorial_tracked_* and eval_boxed_fact_tracked only count down. These
+// return n! for real: each level multiplies the result of the level below (checked_mul),
+// so the call is no longer a tail call and every frame keeps n alive until it returns.
+// The counting rows sit next to them in the table - any gap is the arithmetic.
+
+pub trait FactorialInt: Copy + PartialEq + Sub<Output = Self> + From<u8> + fmt::Display {
+    const MAX_DEPTH: u64; // u8 stops at 255, the same clamp as simple(u8) and boxed(u8)
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn from_depth(n: u64) -> Self;
+    fn make_boxed(n: u64) -> BoxedFact<Self>;
+}
+
+macro_rules! factorial_int {
+    ($t:ty, $max_depth:expr, $make_boxed:expr) => {
+        impl FactorialInt for $t {
+            const MAX_DEPTH: u64 = $max_depth;
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+
+            fn from_depth(n: u64) -> Self {
+                n.min(Self::MAX_DEPTH) as $t
+            }
+
+            fn make_boxed(n: u64) -> BoxedFact<Self> {
+                $make_boxed(Self::from_depth(n))
+            }
+        }
+    };
+}
+
+factorial_int!(u8, u8::MAX as u64, make_boxed_fact_u8);
+factorial_int!(u64, u64::MAX, make_boxed_fact_u64);
+factorial_int!(u128, u64::MAX, make_boxed_fact_u128);
+
+// === fn ธรรมดาที่คูณจริง ===
+pub fn factorial_tracked<T: FactorialInt>(n: T, stack_info: &mut Vec<usize>) -> Option<T> {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n == T::from(0) {
+        return Some(T::from(1));
+    }
+    factorial_tracked(n - T::from(1), stack_info)?.checked_mul(n)
+}
+
+// === BoxedFact ที่คูณจริง ===
+pub fn eval_boxed_factorial_tracked<T: FactorialInt>(
+    f: &BoxedFact<T>,
+    stack_info: &mut Vec<usize>,
+) -> Option<T> {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    match f {
+        BoxedFact::Next(v, next) => eval_boxed_factorial_tracked(next, stack_info)?.checked_mul(*v),
+        BoxedFact::Done(v) => Some(*v),
+    }
+}
+
+// CRITICAL: The first n whose factorial no longer fits - 6 for u8, 21 for u64, 35 for u128
+pub fn first_overflow<T: FactorialInt>() -> u64 {
+    let mut product = T::from(1);
+    let mut n = 1;
+    while let Some(next) = product.checked_mul(T::from_depth(n)) {
+        product = next;
+        n += 1;
+    }
+    n
+}
+
+// Loop, not recursion - the table needs the answer without another deep stack
+pub fn factorial<T: FactorialInt>(n: u64) -> Option<T> {
+    (1..=n).try_fold(T::from(1), |product, k| {
+        product.checked_mul(T::from_depth(k))
+    })
+}
+
+// One fold step of n! over a BoxedFact - fold.rs multiplies its lists with this too
+pub fn checked_product<T: FactorialInt>(product: Option<T>, v: &T) -> Option<T> {
+    product?.checked_mul(*v)
+}
+
+// === จำนวนเต็มไม่จำกัดขนาด ===
+// Little-endian base 2^64 limbs; factorial only ever multiplies by a small number
+#[derive(Clone)]
+pub struct BigUint(Vec<u64>);
+
+impl BigUint {
+    pub fn one() -> Self {
+        BigUint(vec![1])
+    }
+
+    pub fn mul_small(&mut self, rhs: u64) {
+        let mut carry = 0u128;
+        for limb in &mut self.0 {
+            let product = *limb as u128 * rhs as u128 + carry;
+            *limb = product as u64;
+            carry = product >> 64;
+        }
+        if carry > 0 {
+            self.0.push(carry as u64);
+        }
+    }
+
+    // Divides in place and returns the remainder
+    fn div_small(&mut self, rhs: u64) -> u64 {
+        let mut rem = 0u128;
+        for limb in self.0.iter_mut().rev() {
+            let current = (rem << 64) | *limb as u128;
+            *limb = (current / rhs as u128) as u64;
+            rem = current % rhs as u128;
+        }
+        while self.0.len() > 1 && self.0.last() == Some(&0) {
+            self.0.pop();
+        }
+        rem as u64
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.iter().all(|&limb| limb == 0)
+    }
+}
+
+impl fmt::Display for BigUint {
+    // KEY: Peels off 19 decimal digits per division - quadratic, fine at table depths
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const CHUNK: u64 = 10_000_000_000_000_000_000;
+        let mut rest = self.clone();
+        let mut chunks = Vec::new();
+        loop {
+            chunks.push(rest.div_small(CHUNK));
+            if rest.is_zero() {
+                break;
+            }
+        }
+        let mut chunks = chunks.iter().rev();
+        if let Some(first) = chunks.next() {
+            write!(f, "{}", first)?;
+        }
+        for chunk in chunks {
+            write!(f, "{:019}", chunk)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn factorial_big_tracked(n: u64, stack_info: &mut Vec<usize>) -> BigUint {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n == 0 {
+        return BigUint::one();
+    }
+    let mut product = factorial_big_tracked(n - 1, stack_info);
+    product.mul_small(n);
+    product
+}
+
+pub fn factorial_big(n: u64) -> BigUint {
+    let mut product = BigUint::one();
+    for k in 1..=n {
+        product.mul_small(k);
+    }
+    product
+}
+
+fn run_factorial<T: FactorialInt>(n: u64) -> Measurement {
+    measure(
+        n,
+        || T::from_depth(n),
+        |n, stack_info| {
+            black_box(factorial_tracked(*n, stack_info));
+        },
+    )
+}
+
+fn run_boxed_factorial<T: FactorialInt>(n: u64) -> Measurement {
+    measure(
+        n,
+        || T::make_boxed(n),
+        |fact, stack_info| {
+            black_box(eval_boxed_factorial_tracked(fact, stack_info));
+        },
+    )
+}
+
+fn run_factorial_big(n: u64) -> Measurement {
+    measure(
+        n,
+        || n,
+        |n, stack_info| {
+            black_box(factorial_big_tracked(*n, stack_info));
+        },
+    )
+}
+
+pub const SCENARIOS: &[Scenario] = &[
+    Scenario {
+        name: "factorial(u8)",
+        run: run_factorial::<u8>,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "factorial(u64)",
+        run: run_factorial::<u64>,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "factorial(u128)",
+        run: run_factorial::<u128>,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "factorial-boxed(u8)",
+        run: run_boxed_factorial::<u8>,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "factorial-boxed(u64)",
+        run: run_boxed_factorial::<u64>,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "factorial-boxed(u128)",
+        run: run_boxed_factorial::<u128>,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "factorial(BigUint)",
+        run: run_factorial_big,
+        iterative: false,
+        requires_unwind: false,
+    },
+];
+
+// Largest n! that fits, and the first n that overflows
+fn limits<T: FactorialInt>() -> (String, String) {
+    let first = first_overflow::<T>();
+    let largest = factorial::<T>(first - 1).map_or("-".to_string(), |p| p.to_string());
+    (
+        format!("{}! = {}", first - 1, largest),
+        format!("{}!", first),
+    )
+}
+
+fn answer<T: FactorialInt>(n: u64) -> String {
+    let n = n.min(T::MAX_DEPTH);
+    factorial::<T>(n).map_or(format!("{}! overflows", n), |p| format!("{}! = {}", n, p))
+}
+
+// Largest n whose n! is printed in full (34! has 39 digits); above it only the length
+const BIG_ANSWER_MAX: u64 = 34;
+
+// Digits of n! from log10 - building the BigUint at 10^6 would take minutes to print
+pub fn factorial_digits(n: u64) -> u64 {
+    let log10: f64 = (2..=n).map(|k| (k as f64).log10()).sum();
+    log10.floor() as u64 + 1
+}
+
+fn answer_big(n: u64) -> String {
+    if n <= BIG_ANSWER_MAX {
+        format!("{}! = {}", n, factorial_big(n))
+    } else {
+        format!("{}! has {} digits", n, factorial_digits(n))
+    }
+}
+
+type Limits = fn() -> (String, String);
+type Answer = fn(u64) -> String;
+
+// (type, counting scenario, computing scenario, overflow limits, n! at the run's depth)
+pub const ROWS: &[(&str, &str, &str, Option<Limits>, Answer)] = &[
+    (
+        "u8",
+        "simple(u8)",
+        "factorial(u8)",
+        Some(limits::<u8>),
+        answer::<u8>,
+    ),
+    (
+        "u64",
+        "simple(u64)",
+        "factorial(u64)",
+        Some(limits::<u64>),
+        answer::<u64>,
+    ),
+    (
+        "u128",
+        "simple(u128)",
+        "factorial(u128)",
+        Some(limits::<u128>),
+        answer::<u128>,
+    ),
+    (
+        "boxed u8",
+        "boxed(u8)",
+        "factorial-boxed(u8)",
+        Some(limits::<u8>),
+        answer::<u8>,
+    ),
+    (
+        "boxed u64",
+        "boxed(u64)",
+        "factorial-boxed(u64)",
+        Some(limits::<u64>),
+        answer::<u64>,
+    ),
+    (
+        "boxed u128",
+        "boxed(u128)",
+        "factorial-boxed(u128)",
+        Some(limits::<u128>),
+        answer::<u128>,
+    ),
+    (
+        "BigUint",
+        "simple(u64)",
+        "factorial(BigUint)",
+        None,
+        answer_big,
+    ),
+];
+
+// === ตารางเทียบนับถอยหลังกับคูณจริง ===
+pub fn render_table(format: Format, results: &[&ScenarioResult]) -> String {
+    let find = |name: &str| results.iter().copied().find(|r| r.name == name);
+    let depth = results.first().map_or(0, |r| r.depth);
+    let rows: Vec<Vec<String>> = ROWS
+        .iter()
+        .map(|&(ty, counting, computing, limits, answer)| {
+            let (largest, overflow) =
+                limits.map_or(("-".to_string(), "never".to_string()), |limits| limits());
+            // Only answer for depths the computing scenario actually ran at
+            let ran = find(computing).is_some_and(|r| r.report.is_some());
+            vec![
+                ty.to_string(),
+                output::per_level_cell(find(counting)),
+                output::per_level_cell(find(computing)),
+                largest,
+                overflow,
+                if ran { answer(depth) } else { "-".to_string() },
+            ]
+        })
+        .collect();
+    output::render_grid(
+        format,
+        &format!("Counting down vs computing n! (depth {})", depth),
+        &[
+            "Type",
+            "counting B/lvl",
+            "computing B/lvl",
+            "Largest that fits",
+            "First overflow",
+            "Result at depth",
+        ],
+        &rows,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_overflow_per_type() {
+        assert_eq!(first_overflow::<u8>(), 6);
+        assert_eq!(first_overflow::<u64>(), 21);
+        assert_eq!(first_overflow::<u128>(), 35);
+    }
+
+    #[test]
+    fn checked_factorial_stops_at_the_limit() {
+        assert_eq!(factorial::<u8>(5), Some(120));
+        assert_eq!(factorial::<u8>(6), None);
+        assert_eq!(factorial::<u64>(20), Some(2_432_902_008_176_640_000));
+        assert_eq!(factorial::<u64>(21), None);
+        assert!(factorial::<u128>(34).is_some());
+        assert_eq!(factorial::<u128>(35), None);
+    }
+
+    #[test]
+    fn tracked_and_boxed_agree_with_the_loop() {
+        for n in [0u64, 1, 5, 20, 21] {
+            let tracked = factorial_tracked(n, &mut Vec::new());
+            let boxed = eval_boxed_factorial_tracked(&u64::make_boxed(n), &mut Vec::new());
+            assert_eq!(tracked, factorial::<u64>(n), "{}!", n);
+            assert_eq!(boxed, factorial::<u64>(n), "boxed {}!", n);
+        }
+    }
+
+    #[test]
+    fn biguint_display_matches_known_factorials() {
+        for (n, expected) in [
+            (0, "1"),
+            (1, "1"),
+            (5, "120"),
+            (20, "2432902008176640000"),
+            (21, "51090942171709440000"),
+            (25, "15511210043330985984000000"),
+            (30, "265252859812191058636308480000000"),
+            (
+                50,
+                "30414093201713378043612608166064768844377641568960512000000000000",
+            ),
+        ] {
+            assert_eq!(factorial_big(n).to_string(), expected, "{}!", n);
+        }
+    }
+
+    #[test]
+    fn biguint_display_keeps_inner_zero_chunks() {
+        let mut value = BigUint::one();
+        value.mul_small(10_000_000_000_000_000_000); // 10^19: the low chunk is all zeros
+        assert_eq!(value.to_string(), format!("1{}", "0".repeat(19)));
+    }
+
+    #[test]
+    fn digit_count_matches_biguint() {
+        for n in [0, 1, 9, 10, 25, 100, 500] {
+            let digits = factorial_big(n).to_string().len() as u64;
+            assert_eq!(factorial_digits(n), digits, "{}!", n);
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use crate::scenarios::{measure, Measurement, Scenario};
+use crate::{eval_boxed_string_tracked, make_boxed_fact_u64, make_boxed_string, BoxedFact};
+use stacker::remaining_stack;
+use std::fmt::Write;
+
+// IMPORTANT: simple_string_tracked runs format! inside every frame while
+// eval_boxed_string_tracked only push_str's preformatted pieces, so "string(pure)" vs
+// "string(boxed)" compares different work, not boxing. Each pair below does IDENTICAL
+// per-level work in both variants - only the recursion shape differs.
+
+// === คู่ที่ 1: format ไว้ก่อนทั้งคู่ (preformatted) ===
+// Same pieces, same order as make_boxed_string: "1-", "2-", ..., "n-", "0-"
+pub fn preformat_pieces(n: u64) -> Vec<String> {
+    let mut pieces: Vec<String> = (1..=n).map(|i| format!("{}-", i)).collect();
+    pieces.push(format!("{}-", 0));
+    pieces
+}
+
+pub fn pure_string_preformatted_tracked(
+    pieces: &[String],
+    stack_info: &mut Vec<usize>,
+    out: &mut String,
+) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if let Some((first, rest)) = pieces.split_first() {
+        out.push_str(first);
+        if !rest.is_empty() {
+            pure_string_preformatted_tracked(rest, stack_info, out);
+        }
+    }
+}
+
+// === คู่ที่ 2: format ด้วย write! ในทุกชั้นทั้งคู่ ===
+pub fn pure_string_write_tracked(n: u64, stack_info: &mut Vec<usize>, out: &mut String) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    let _ = write!(out, "{}-", n);
+    if n > 0 {
+        pure_string_write_tracked(n - 1, stack_info, out);
+    }
+}
+
+pub fn eval_boxed_string_write_tracked(
+    f: &BoxedFact<u64>,
+    stack_info: &mut Vec<usize>,
+    out: &mut String,
+) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    match f {
+        BoxedFact::Next(v, next) => {
+            let _ = write!(out, "{}-", v);
+            eval_boxed_string_write_tracked(next, stack_info, out);
+        }
+        BoxedFact::Done(v) => {
+            let _ = write!(out, "{}-", v);
+        }
+    }
+}
+
+// === คู่ที่ 3: itoa buffer บน stack ทั้งคู่ ===
+// CRITICAL: inline(always) puts the 20-byte digit buffer into the recursive frame itself,
+// exactly as an itoa-style formatter would in both variants
+#[inline(always)]
+fn push_decimal(out: &mut String, mut v: u64) {
+    let mut buf = [0u8; 20];
+    let mut i = buf.len();
+    loop {
+        i -= 1;
+        buf[i] = b'0' + (v % 10) as u8;
+        v /= 10;
+        if v == 0 {
+            break;
+        }
+    }
+    // Only ASCII digits were written
+    out.push_str(std::str::from_utf8(&buf[i..]).unwrap_or_default());
+    out.push('-');
+}
+
+pub fn pure_string_itoa_tracked(n: u64, stack_info: &mut Vec<usize>, out: &mut String) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    push_decimal(out, n);
+    if n > 0 {
+        pure_string_itoa_tracked(n - 1, stack_info, out);
+    }
+}
+
+pub fn eval_boxed_string_itoa_tracked(
+    f: &BoxedFact<u64>,
+    stack_info: &mut Vec<usize>,
+    out: &mut String,
+) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    match f {
+        BoxedFact::Next(v, next) => {
+            push_decimal(out, *v);
+            eval_boxed_string_itoa_tracked(next, stack_info, out);
+        }
+        BoxedFact::Done(v) => push_decimal(out, *v),
+    }
+}
+
+pub const SCENARIOS: &[Scenario] = &[
+    Scenario {
+        name: "string-preformatted(pure)",
+        run: run_preformatted_pure,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "string-preformatted(boxed)",
+        run: run_preformatted_boxed,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "string-write(pure)",
+        run: run_write_pure,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "string-write(boxed)",
+        run: run_write_boxed,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "string-itoa(pure)",
+        run: run_itoa_pure,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "string-itoa(boxed)",
+        run: run_itoa_boxed,
+        iterative: false,
+        requires_unwind: false,
+    },
+];
+
+fn output_buffer(n: u64) -> String {
+    String::with_capacity((n as usize + 1) * 21)
+}
+
+fn run_preformatted_pure(n: u64) -> Measurement {
+    measure(
+        n,
+        || (preformat_pieces(n), output_buffer(n)),
+        |(pieces, out), stack_info| pure_string_preformatted_tracked(pieces, stack_info, out),
+    )
+}
+
+fn run_preformatted_boxed(n: u64) -> Measurement {
+    measure(
+        n,
+        || (make_boxed_string(n), output_buffer(n)),
+        |(tree, out), stack_info| eval_boxed_string_tracked(tree, stack_info, out),
+    )
+}
+
+fn run_write_pure(n: u64) -> Measurement {
+    measure(
+        n,
+        || output_buffer(n),
+        |out, stack_info| pure_string_write_tracked(n, stack_info, out),
+    )
+}
+
+fn run_write_boxed(n: u64) -> Measurement {
+    measure(
+        n,
+        || (make_boxed_fact_u64(n), output_buffer(n)),
+        |(fact, out), stack_info| eval_boxed_string_write_tracked(fact, stack_info, out),
+    )
+}
+
+fn run_itoa_pure(n: u64) -> Measurement {
+    measure(
+        n,
+        || output_buffer(n),
+        |out, stack_info| pure_string_itoa_tracked(n, stack_info, out),
+    )
+}
+
+fn run_itoa_boxed(n: u64) -> Measurement {
+    measure(
+        n,
+        || (make_boxed_fact_u64(n), output_buffer(n)),
+        |(fact, out), stack_info| eval_boxed_string_itoa_tracked(fact, stack_info, out),
+    )
+}
@@ -0,0 +1,141 @@
+use crate::factorial;
+use crate::output::{self, Format};
+use crate::scenarios::{measure, Measurement, Scenario, ScenarioResult};
+use crate::{make_boxed_fact_u64, make_boxed_string};
+use stacker::remaining_stack;
+use std::hint::black_box;
+
+// IMPORTANT: The project is named after factorial but eval_boxed_fact_tracked only walks
+// the list. fold_tracked does the walk AND the multiplication in a loop, so the only
+// stack is the fold's own frame - compare its row with boxed(u64) and string(boxed).
+
+// KEY: One sample from the caller before the fold starts, so `used` is the fold's own frame
+// - the constant stack it needs at any depth - instead of 0 from samples all taken inside it
+fn sample_caller(stack_info: &mut Vec<usize>) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+}
+
+// make_boxed_fact_u64(n) holds n, n-1, ..., 1 and Done(1), so the product is n! - folded
+// with the same checked step as factorial.rs, None from 21! on
+fn run_fold_fact(n: u64) -> Measurement {
+    measure(
+        n,
+        || make_boxed_fact_u64(n),
+        |fact, stack_info| {
+            sample_caller(stack_info);
+            let product = fact.fold_tracked(Some(1u64), factorial::checked_product, stack_info);
+            black_box(product);
+        },
+    )
+}
+
+fn run_fold_string(n: u64) -> Measurement {
+    measure(
+        n,
+        || make_boxed_string(n),
+        |strings, stack_info| {
+            sample_caller(stack_info);
+            let out = strings.fold_tracked(
+                String::with_capacity(n as usize * 8),
+                |mut out, s| {
+                    out.push_str(s);
+                    out
+                },
+                stack_info,
+            );
+            black_box(out);
+        },
+    )
+}
+
+pub const SCENARIOS: &[Scenario] = &[
+    Scenario {
+        name: "fold(BoxedFact u64)",
+        run: run_fold_fact,
+        iterative: true,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "fold(BoxedString)",
+        run: run_fold_string,
+        iterative: true,
+        requires_unwind: false,
+    },
+];
+
+fn factorial_cell(n: u64) -> String {
+    match make_boxed_fact_u64(n).fold(Some(1), factorial::checked_product) {
+        Some(product) => format!("{}! = {}", n, product),
+        None => format!("{}! overflows u64", n),
+    }
+}
+
+fn concat_cell(n: u64) -> String {
+    let strings = make_boxed_string(n);
+    format!("{} bytes", strings.fold(0, |len, s| len + s.len()))
+}
+
+type Answer = fn(u64) -> String;
+
+// (structure, recursive scenario, fold scenario, what the fold computes at that depth)
+pub const PAIRS: &[(&str, &str, &str, Answer)] = &[
+    (
+        "BoxedFact<u64>",
+        "boxed(u64)",
+        "fold(BoxedFact u64)",
+        factorial_cell,
+    ),
+    (
+        "BoxedString",
+        "string(boxed)",
+        "fold(BoxedString)",
+        concat_cell,
+    ),
+];
+
+// The fold is flat by design, so its column is the constant stack it used, not bytes/level
+fn fold_stack_cell(result: Option<&ScenarioResult>) -> String {
+    match result.and_then(|r| r.report.as_ref()) {
+        Some(report) => report.used.to_string(),
+        None => output::per_level_cell(result),
+    }
+}
+
+// === ตารางเทียบ recursion กับ fold ===
+pub fn render_table(format: Format, results: &[&ScenarioResult]) -> String {
+    let find = |name: &str| results.iter().copied().find(|r| r.name == name);
+    let eval = |name: &str| {
+        find(name)
+            .and_then(|r| r.timing.as_ref())
+            .map_or("-".to_string(), |t| format!("{:.1}", t.eval_ns_per_level))
+    };
+    let depth = results.first().map_or(0, |r| r.depth);
+    let rows: Vec<Vec<String>> = PAIRS
+        .iter()
+        .map(|&(structure, recursive, fold, answer)| {
+            vec![
+                structure.to_string(),
+                output::per_level_cell(find(recursive)),
+                fold_stack_cell(find(fold)),
+                eval(recursive),
+                eval(fold),
+                answer(depth),
+            ]
+        })
+        .collect();
+    output::render_grid(
+        format,
+        &format!("Recursive eval vs stack-safe fold (depth {})", depth),
+        &[
+            "Structure",
+            "recursive B/lvl",
+            "fold stack (bytes)",
+            "recursive ns/lvl",
+            "fold ns/lvl",
+            "Fold result",
+        ],
+        &rows,
+    )
+}
@@ -0,0 +1,190 @@
+use crate::scenarios::{measure, Measurement, Scenario};
+use stacker::remaining_stack;
+use std::hint::black_box;
+
+// IMPORTANT: BoxedFact grown into real AST nodes - the classic place where boxing is used.
+// eval walks the Box tree recursively. Like tree.rs, it samples only on reaching a new
+// maximum eval depth, so per level means per eval frame even where a node has several
+// children. Programs are generated with loops, never recursively, to reach any nesting.
+
+// === AST ===
+pub enum Expr {
+    Num(i64),
+    Var(usize), // 0 = innermost let binding
+    Add(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    If(Box<Expr>, Box<Expr>, Box<Expr>), // Non-zero condition takes the first branch
+    Let(Box<Expr>, Box<Expr>),           // Binds the value for the body
+}
+
+// === evaluator ===
+pub fn eval_tracked(
+    e: &Expr,
+    env: &mut Vec<i64>,
+    depth: usize,
+    stack_info: &mut Vec<usize>,
+) -> Result<i64, String> {
+    if depth == stack_info.len() {
+        if let Some(rem) = remaining_stack() {
+            stack_info.push(rem);
+        }
+    }
+    let next = depth + 1;
+    Ok(match e {
+        Expr::Num(v) => *v,
+        Expr::Var(i) => env
+            .len()
+            .checked_sub(i + 1)
+            .map(|slot| env[slot])
+            .ok_or_else(|| format!("unbound variable #{}", i))?,
+        Expr::Add(a, b) => eval_tracked(a, env, next, stack_info)?
+            .wrapping_add(eval_tracked(b, env, next, stack_info)?),
+        Expr::Mul(a, b) => eval_tracked(a, env, next, stack_info)?
+            .wrapping_mul(eval_tracked(b, env, next, stack_info)?),
+        Expr::If(cond, then, otherwise) => {
+            if eval_tracked(cond, env, next, stack_info)? != 0 {
+                eval_tracked(then, env, next, stack_info)?
+            } else {
+                eval_tracked(otherwise, env, next, stack_info)?
+            }
+        }
+        Expr::Let(value, body) => {
+            let value = eval_tracked(value, env, next, stack_info)?;
+            env.push(value);
+            let result = eval_tracked(body, env, next, stack_info);
+            env.pop();
+            result?
+        }
+    })
+}
+
+// === โปรแกรมที่สร้างขึ้น (nesting ตาม n) ===
+// (+ n (+ n-1 (... (+ 1 0))))
+pub fn nested_add(n: u64) -> Expr {
+    let mut current = Expr::Num(0);
+    for i in 1..=n {
+        current = Expr::Add(Box::new(Expr::Num(i as i64)), Box::new(current));
+    }
+    current
+}
+
+// (let 1 (+ x (let 2 (+ x (... (let n x))))))
+pub fn nested_let(n: u64) -> Expr {
+    let mut current = Expr::Let(Box::new(Expr::Num(n as i64)), Box::new(Expr::Var(0)));
+    for i in (1..n).rev() {
+        let body = Expr::Add(Box::new(Expr::Var(0)), Box::new(current));
+        current = Expr::Let(Box::new(Expr::Num(i as i64)), Box::new(body));
+    }
+    current
+}
+
+// (if 1 (if 1 (... (* 6 7)) 0) 0)
+pub fn nested_if(n: u64) -> Expr {
+    let mut current = Expr::Mul(Box::new(Expr::Num(6)), Box::new(Expr::Num(7)));
+    for _ in 0..n {
+        current = Expr::If(
+            Box::new(Expr::Num(1)),
+            Box::new(current),
+            Box::new(Expr::Num(0)),
+        );
+    }
+    current
+}
+
+// nested add and nested let both sum 1..=n
+fn sum_to(n: u64) -> i64 {
+    (n as i64) * (n as i64 + 1) / 2
+}
+
+// Like the parser scenarios, an error or a wrong value fails the run
+fn run_program(n: u64, generate: fn(u64) -> Expr, expected: i64) -> Measurement {
+    measure(
+        n,
+        || (generate(n), Vec::new()),
+        |(program, env), stack_info| {
+            let value = black_box(eval_tracked(program, env, 0, stack_info));
+            assert_eq!(value, Ok(expected), "program of depth {}", n);
+        },
+    )
+}
+
+pub const SCENARIOS: &[Scenario] = &[
+    Scenario {
+        name: "interp(nested add)",
+        run: |n| run_program(n, nested_add, sum_to(n)),
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "interp(nested let)",
+        run: |n| run_program(n, nested_let, sum_to(n)),
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "interp(nested if)",
+        run: |n| run_program(n, nested_if, 42),
+        iterative: false,
+        requires_unwind: false,
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(e: &Expr) -> Result<i64, String> {
+        eval_tracked(e, &mut Vec::new(), 0, &mut Vec::new())
+    }
+
+    #[test]
+    fn generated_programs_evaluate() {
+        for n in [1, 2, 10, 100] {
+            let sum = sum_to(n);
+            assert_eq!(eval(&nested_add(n)), Ok(sum), "nested add {}", n);
+            assert_eq!(eval(&nested_let(n)), Ok(sum), "nested let {}", n);
+            assert_eq!(eval(&nested_if(n)), Ok(42), "nested if {}", n);
+        }
+    }
+
+    #[test]
+    fn let_binds_innermost_first() {
+        let program = Expr::Let(
+            Box::new(Expr::Num(1)),
+            Box::new(Expr::Let(
+                Box::new(Expr::Num(2)),
+                Box::new(Expr::Mul(
+                    Box::new(Expr::Var(0)),
+                    Box::new(Expr::Add(Box::new(Expr::Var(1)), Box::new(Expr::Num(10)))),
+                )),
+            )),
+        );
+        assert_eq!(eval(&program), Ok(22));
+    }
+
+    #[test]
+    fn if_takes_the_else_branch_on_zero() {
+        let program = Expr::If(
+            Box::new(Expr::Num(0)),
+            Box::new(Expr::Num(1)),
+            Box::new(Expr::Num(2)),
+        );
+        assert_eq!(eval(&program), Ok(2));
+    }
+
+    #[test]
+    fn unbound_variable_is_an_error() {
+        assert!(eval(&Expr::Var(0)).is_err());
+        let mut env = Vec::new();
+        let program = Expr::Let(Box::new(Expr::Num(1)), Box::new(Expr::Var(1)));
+        assert!(eval_tracked(&program, &mut env, 0, &mut Vec::new()).is_err());
+        assert!(env.is_empty(), "let must pop its binding on error");
+    }
+
+    #[test]
+    fn samples_once_per_eval_depth() {
+        let mut stack_info = Vec::new();
+        eval_tracked(&nested_add(10), &mut Vec::new(), 0, &mut stack_info).unwrap();
+        assert_eq!(stack_info.len(), 11);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::scenarios::{measure, Measurement, Scenario};
+use crate::{make_boxed_fact_u64, make_boxed_string};
+use stacker::remaining_stack;
+use std::cell::RefCell;
+use std::hint::black_box;
+use std::iter;
+use std::rc::Rc;
+
+// IMPORTANT: Wrapping a Box<dyn Iterator> in another adapter and boxing it again builds a
+// chain where next() on the outside calls next() on the layer below through a vtable, all
+// the way down to the source. One next() therefore recurses once per layer - the depth of
+// the chain, not the number of items. BoxedFact::iter() and BoxedString::iter() walk with a
+// loop instead.
+
+pub type DynIter = Box<dyn Iterator<Item = u64>>;
+
+// CRITICAL: The layers cannot share &mut stack_info, so each one samples into a shared
+// buffer. Samples arrive innermost first (each adapter runs after the layer below
+// returns) and are copied out outermost first, the order analyze_stack expects.
+pub struct Probe(RefCell<Vec<usize>>);
+
+impl Probe {
+    pub fn with_capacity(n: u64) -> Rc<Probe> {
+        Rc::new(Probe(RefCell::new(Vec::with_capacity(n as usize + 2))))
+    }
+
+    pub fn sample(&self) {
+        if let Some(rem) = remaining_stack() {
+            self.0.borrow_mut().push(rem);
+        }
+    }
+
+    pub fn drain_into(&self, stack_info: &mut Vec<usize>) {
+        stack_info.extend(self.0.borrow_mut().drain(..).rev());
+    }
+}
+
+// === Box<dyn Iterator> ซ้อนด้วย .map() ===
+pub fn build_map_chain(depth: u64, probe: &Rc<Probe>) -> DynIter {
+    // IMPORTANT: Iterative creation - only next() recurses
+    let mut current: DynIter = Box::new(iter::once(1));
+    for _ in 0..depth {
+        let probe = Rc::clone(probe);
+        current = Box::new(current.map(move |x| {
+            probe.sample();
+            x + 1
+        }));
+    }
+    current
+}
+
+// === Box<dyn Iterator> ซ้อนด้วย .chain() ===
+// The map chain's adapter doubles as the probe; here the probe is an extra inspect()
+pub fn build_chain_chain(depth: u64, probe: &Rc<Probe>) -> DynIter {
+    let mut current: DynIter = Box::new(iter::once(1));
+    for i in 0..depth {
+        let probe = Rc::clone(probe);
+        current = Box::new(
+            current
+                .chain(iter::once(i))
+                .inspect(move |_| probe.sample()),
+        );
+    }
+    current
+}
+
+fn run_chain(n: u64, build: fn(u64, &Rc<Probe>) -> DynIter) -> Measurement {
+    measure(
+        n,
+        || {
+            let probe = Probe::with_capacity(n);
+            let chain = build(n, &probe);
+            (probe, chain)
+        },
+        |(probe, chain), stack_info| {
+            // One next() is the whole recursion; copying the samples out is not
+            black_box(chain.next());
+            probe.drain_into(stack_info);
+        },
+    )
+}
+
+fn run_flat(n: u64) -> Measurement {
+    measure(
+        n,
+        || make_boxed_fact_u64(n),
+        |fact, stack_info| {
+            // Samples on every next(), so a flat iterator shows up as a flat stack
+            let sum = fact
+                .iter()
+                .inspect(|_| {
+                    if let Some(rem) = remaining_stack() {
+                        stack_info.push(rem);
+                    }
+                })
+                .fold(0u64, |sum, &v| sum.wrapping_add(v));
+            black_box(sum);
+        },
+    )
+}
+
+fn run_flat_string(n: u64) -> Measurement {
+    measure(
+        n,
+        || make_boxed_string(n),
+        |strings, stack_info| {
+            let mut out = String::with_capacity(n as usize * 8);
+            for s in &*strings {
+                if let Some(rem) = remaining_stack() {
+                    stack_info.push(rem);
+                }
+                out.push_str(s);
+            }
+            black_box(out);
+        },
+    )
+}
+
+pub const SCENARIOS: &[Scenario] = &[
+    Scenario {
+        name: "iter-chain(map)",
+        run: |n| run_chain(n, build_map_chain),
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "iter-chain(chain)",
+        run: |n| run_chain(n, build_chain_chain),
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "iter-flat(BoxedFact)",
+        run: run_flat,
+        iterative: true,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "iter-flat(BoxedString)",
+        run: run_flat_string,
+        iterative: true,
+        requires_unwind: false,
+    },
+];
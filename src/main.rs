@@ -1,4 +1,38 @@
+mod alignment;
+#[cfg(feature = "alloc-count")]
+mod alloc_count;
+mod async_rec;
+mod cli;
+mod closure;
+mod collection;
+mod dyn_node;
+mod factorial;
+mod fair_string;
+mod fold;
+mod interp;
+mod iter_chain;
+mod matrix;
+mod metadata;
+mod mutual;
+mod output;
+mod parser;
+mod passing;
+mod payload;
+mod propagate;
+#[cfg(feature = "rayon")]
+mod rayon_pool;
+mod scenarios;
+mod sret;
+mod stats;
+#[cfg(feature = "tokio")]
+mod tokio_rt;
+mod tree;
+mod unwind;
+#[cfg(any(feature = "tokio", feature = "rayon"))]
+mod workers;
+
 use stacker::remaining_stack;
+use std::fmt;
 
 // IMPORTANT: This file demonstrates why boxed recursion uses the same stack as pure recursion
 // KEY INSIGHT: Stack memory is dominated by function call overhead, not data size or boxing
@@ -138,9 +172,42 @@ pub fn eval_boxed_string_tracked(f: &BoxedString, stack_info: &mut Vec<usize>, o
 }
 
 // === วิเคราะห์และแสดงผล ===
+// IMPORTANT: Below this many bytes per level the frames are being reused, not stacked.
+// In release builds LLVM can turn simple_factorial_tracked_u64 into a loop, and the
+// resulting ~0 bytes/level must NOT be reported as a real measurement.
+const TAIL_CALL_BYTES_PER_LEVEL: f64 = 1.0;
+
+pub struct StackReport {
+    pub used: usize,    // Total stack consumed (bytes)
+    pub per_level: f64, // Average bytes per recursion level
+    pub levels: usize,  // Number of samples taken
+    pub tail_call_eliminated: bool,
+}
+
+impl fmt::Display for StackReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bytes ({:.2} per level)", self.used, self.per_level)?;
+        if self.tail_call_eliminated {
+            write!(f, " [tail-call eliminated]")?;
+        }
+        Ok(())
+    }
+}
+
+// CRITICAL: Tail-call detection - if no sample ever moves deeper than the first one,
+// the "recursion" ran in a single frame and the optimizer replaced it with a loop
+fn is_tail_call_eliminated(stack_info: &[usize], per_level: f64) -> bool {
+    let deepest_step = stack_info
+        .windows(2)
+        .map(|w| w[0].saturating_sub(w[1]))
+        .max()
+        .unwrap_or(0);
+    deepest_step == 0 || per_level < TAIL_CALL_BYTES_PER_LEVEL
+}
+
 // CRITICAL: Stack analysis function - calculates total usage and per-level cost
 // KEY METRIC: per_level shows why u8=u64 and why boxed=pure for simple recursion
-fn analyze_stack(stack_info: &[usize]) -> Option<(usize, f64)> {
+fn analyze_stack(stack_info: &[usize]) -> Option<StackReport> {
     if stack_info.len() < 2 {
         return None;
     }
@@ -148,9 +215,15 @@ fn analyze_stack(stack_info: &[usize]) -> Option<(usize, f64)> {
     let end = *stack_info.iter().min().unwrap_or(&start); // Minimum remaining stack
     let used = start.saturating_sub(end); // Total stack consumed
     let per_level = used as f64 / stack_info.len() as f64; // IMPORTANT: Per-call overhead
-    Some((used, per_level))
+    Some(StackReport {
+        used,
+        per_level,
+        levels: stack_info.len(),
+        tail_call_eliminated: is_tail_call_eliminated(stack_info, per_level),
+    })
 }
 
+#[allow(dead_code)] // Only used by the commented-out sweep in main()
 fn run_one_case(n: u64) {
     // IMPORTANT: This function demonstrates the key findings
     // Run multiple data types to show: u8 = u64 ≠ u128, boxed = pure (mostly)
@@ -163,11 +236,8 @@ fn run_one_case(n: u64) {
         simple_factorial_tracked_u8(n as u8, &mut s8_stack)
     }));
     if result.is_ok() {
-        if let Some((used, per_level)) = analyze_stack(&s8_stack) {
-            println!(
-                "simple(u8): total used {} bytes ({:.2} per level)",
-                used, per_level
-            );
+        if let Some(report) = analyze_stack(&s8_stack) {
+            println!("simple(u8): total used {}", report);
         }
     } else {
         println!("simple(u8): stack overflow!");
@@ -180,11 +250,8 @@ fn run_one_case(n: u64) {
         simple_factorial_tracked_u64(n, &mut s_stack)
     }));
     if result.is_ok() {
-        if let Some((used, per_level)) = analyze_stack(&s_stack) {
-            println!(
-                "simple(u64): total used {} bytes ({:.2} per level)",
-                used, per_level
-            );
+        if let Some(report) = analyze_stack(&s_stack) {
+            println!("simple(u64): total used {}", report);
         }
     } else {
         println!("simple(u64): stack overflow!");
@@ -197,11 +264,8 @@ fn run_one_case(n: u64) {
         simple_factorial_tracked_u128(n as u128, &mut s128_stack)
     }));
     if result.is_ok() {
-        if let Some((used, per_level)) = analyze_stack(&s128_stack) {
-            println!(
-                "simple(u128): total used {} bytes ({:.2} per level)",
-                used, per_level
-            );
+        if let Some(report) = analyze_stack(&s128_stack) {
+            println!("simple(u128): total used {}", report);
         }
     } else {
         println!("simple(u128): stack overflow!");
@@ -217,11 +281,8 @@ fn run_one_case(n: u64) {
             eval_boxed_fact_tracked(&fact, &mut b8_stack)
         }));
         if res.is_ok() {
-            if let Some((used, per_level)) = analyze_stack(&b8_stack) {
-                println!(
-                    "boxed(u8): total used {} bytes ({:.2} per level)",
-                    used, per_level
-                );
+            if let Some(report) = analyze_stack(&b8_stack) {
+                println!("boxed(u8): total used {}", report);
             }
         } else {
             println!("boxed(u8): overflow while evaluating!");
@@ -239,11 +300,8 @@ fn run_one_case(n: u64) {
             eval_boxed_fact_tracked(&fact, &mut b_stack)
         }));
         if res.is_ok() {
-            if let Some((used, per_level)) = analyze_stack(&b_stack) {
-                println!(
-                    "boxed(u64): total used {} bytes ({:.2} per level)",
-                    used, per_level
-                );
+            if let Some(report) = analyze_stack(&b_stack) {
+                println!("boxed(u64): total used {}", report);
             }
         } else {
             println!("boxed(u64): overflow while evaluating!");
@@ -264,11 +322,8 @@ fn run_one_case(n: u64) {
             eval_boxed_fact_tracked(&fact, &mut b128_stack)
         }));
         if res.is_ok() {
-            if let Some((used, per_level)) = analyze_stack(&b128_stack) {
-                println!(
-                    "boxed(u128): total used {} bytes ({:.2} per level)",
-                    used, per_level
-                );
+            if let Some(report) = analyze_stack(&b128_stack) {
+                println!("boxed(u128): total used {}", report);
             }
         } else {
             println!("boxed(u128): overflow while evaluating!");
@@ -285,11 +340,8 @@ fn run_one_case(n: u64) {
         simple_string_tracked(n, &mut str_stack, &mut s)
     }));
     if result.is_ok() {
-        if let Some((used, per_level)) = analyze_stack(&str_stack) {
-            println!(
-                "string(pure): total used {} bytes ({:.2} per level)",
-                used, per_level
-            );
+        if let Some(report) = analyze_stack(&str_stack) {
+            println!("string(pure): total used {}", report);
         }
     } else {
         println!("string(pure): stack overflow!");
@@ -306,11 +358,8 @@ fn run_one_case(n: u64) {
             eval_boxed_string_tracked(&tree, &mut stack_s, &mut out)
         }));
         if res.is_ok() {
-            if let Some((used, per_level)) = analyze_stack(&stack_s) {
-                println!(
-                    "string(boxed): total used {} bytes ({:.2} per level)",
-                    used, per_level
-                );
+            if let Some(report) = analyze_stack(&stack_s) {
+                println!("string(boxed): total used {}", report);
             }
         } else {
             println!("string(boxed): overflow while evaluating!");
@@ -321,6 +370,16 @@ fn run_one_case(n: u64) {
 }
 
 fn main() {
+    // IMPORTANT: Any argument switches to the scenario runner (see `--help`)
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        if let Err(e) = cli::run(&args) {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+        return;
+    }
+
     // IMPORTANT: This program demonstrates surprising truths about stack memory usage!
     // EXPECTED RESULTS:
     // - u8, u64, boxed(u8), boxed(u64): all ~80 bytes/level (IDENTICAL!)
@@ -331,6 +390,8 @@ fn main() {
 
     println!("=== Stack memory usage per recursion level ===");
     println!("(lower = uses less stack per call)");
+    println!("\n=== Build & environment ===");
+    print!("{}", metadata::collect(None));
     println!("\nKEY INSIGHTS TO WATCH FOR:");
     println!("1. u8 = u64 = boxed(u8) = boxed(u64) (~80 bytes/level)");
     println!("2. boxed(u128) < u128 (boxing HELPS with large data)");
@@ -352,11 +413,8 @@ fn main() {
     }));
     match simple_result {
         Ok(_) => {
-            if let Some((used, per_level)) = analyze_stack(&simple_stack) {
-                println!(
-                    "simple(u128): SUCCESS - {} bytes ({:.2} per level)",
-                    used, per_level
-                );
+            if let Some(report) = analyze_stack(&simple_stack) {
+                println!("simple(u128): SUCCESS - {}", report);
             }
         }
         Err(_) => println!("simple(u128): STACK OVERFLOW"),
@@ -372,15 +430,12 @@ fn main() {
         Ok(ref fact) => {
             println!("boxed(u128): Creation successful");
             let boxed_eval = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                eval_boxed_fact_tracked(&fact, &mut boxed_stack)
+                eval_boxed_fact_tracked(fact, &mut boxed_stack)
             }));
             match boxed_eval {
                 Ok(_) => {
-                    if let Some((used, per_level)) = analyze_stack(&boxed_stack) {
-                        println!(
-                            "boxed(u128): SUCCESS - {} bytes ({:.2} per level)",
-                            used, per_level
-                        );
+                    if let Some(report) = analyze_stack(&boxed_stack) {
+                        println!("boxed(u128): SUCCESS - {}", report);
                     }
                 }
                 Err(_) => println!("boxed(u128): STACK OVERFLOW during evaluation"),
@@ -410,15 +465,12 @@ fn main() {
         Ok(ref fact_high) => {
             println!("boxed(u128): ✅ Creation successful");
             let boxed_eval_high = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                eval_boxed_fact_tracked(&fact_high, &mut boxed_stack_high)
+                eval_boxed_fact_tracked(fact_high, &mut boxed_stack_high)
             }));
             match boxed_eval_high {
                 Ok(_) => {
-                    if let Some((used, per_level)) = analyze_stack(&boxed_stack_high) {
-                        println!(
-                            "boxed(u128): ✅ SUCCESS - {} bytes ({:.2} per level)",
-                            used, per_level
-                        );
+                    if let Some(report) = analyze_stack(&boxed_stack_high) {
+                        println!("boxed(u128): ✅ SUCCESS - {}", report);
                         println!(
                             "🎯 BOXED u128 HANDLES {} LEVELS WHERE SIMPLE u128 WOULD OVERFLOW!",
                             high_depth
@@ -448,15 +500,12 @@ fn main() {
         Ok(ref fact_extreme) => {
             println!("boxed(u128): ✅ Creation successful at extreme depth!");
             let boxed_eval_extreme = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                eval_boxed_fact_tracked(&fact_extreme, &mut boxed_stack_extreme)
+                eval_boxed_fact_tracked(fact_extreme, &mut boxed_stack_extreme)
             }));
             match boxed_eval_extreme {
                 Ok(_) => {
-                    if let Some((used, per_level)) = analyze_stack(&boxed_stack_extreme) {
-                        println!(
-                            "boxed(u128): ✅ SUCCESS - {} bytes ({:.2} per level)",
-                            used, per_level
-                        );
+                    if let Some(report) = analyze_stack(&boxed_stack_extreme) {
+                        println!("boxed(u128): ✅ SUCCESS - {}", report);
                         println!(
                             "🏆 BOXED u128 ACHIEVES {} LEVELS! (simple u128 max ~71,000)",
                             extreme_depth
@@ -487,11 +536,8 @@ fn main() {
     }));
     match pure_result {
         Ok(_) => {
-            if let Some((used, per_level)) = analyze_stack(&pure_str_stack) {
-                println!(
-                    "string(pure): SUCCESS - {} bytes ({:.2} per level)",
-                    used, per_level
-                );
+            if let Some(report) = analyze_stack(&pure_str_stack) {
+                println!("string(pure): SUCCESS - {}", report);
             }
         }
         Err(_) => println!("string(pure): STACK OVERFLOW"),
@@ -507,15 +553,12 @@ fn main() {
         Ok(ref str_tree) => {
             println!("string(boxed): Creation successful");
             let boxed_str_eval = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                eval_boxed_string_tracked(&str_tree, &mut boxed_str_stack, &mut boxed_string)
+                eval_boxed_string_tracked(str_tree, &mut boxed_str_stack, &mut boxed_string)
             }));
             match boxed_str_eval {
                 Ok(_) => {
-                    if let Some((used, per_level)) = analyze_stack(&boxed_str_stack) {
-                        println!(
-                            "string(boxed): SUCCESS - {} bytes ({:.2} per level)",
-                            used, per_level
-                        );
+                    if let Some(report) = analyze_stack(&boxed_str_stack) {
+                        println!("string(boxed): SUCCESS - {}", report);
                     }
                 }
                 Err(_) => println!("string(boxed): STACK OVERFLOW during evaluation"),
@@ -550,11 +593,8 @@ fn main() {
     }));
     match high_pure_result {
         Ok(_) => {
-            if let Some((used, per_level)) = analyze_stack(&high_pure_str_stack) {
-                println!(
-                    "string(pure): SUCCESS - {} bytes ({:.2} per level)",
-                    used, per_level
-                );
+            if let Some(report) = analyze_stack(&high_pure_str_stack) {
+                println!("string(pure): SUCCESS - {}", report);
             }
         }
         Err(_) => println!("string(pure): ❌ STACK OVERFLOW at depth 30,000"),
@@ -575,18 +615,15 @@ fn main() {
             let boxed_str_eval_high =
                 std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
                     eval_boxed_string_tracked(
-                        &str_tree_high,
+                        str_tree_high,
                         &mut boxed_str_stack_high,
                         &mut boxed_string_high,
                     )
                 }));
             match boxed_str_eval_high {
                 Ok(_) => {
-                    if let Some((used, per_level)) = analyze_stack(&boxed_str_stack_high) {
-                        println!(
-                            "string(boxed): ✅ SUCCESS - {} bytes ({:.2} per level)",
-                            used, per_level
-                        );
+                    if let Some(report) = analyze_stack(&boxed_str_stack_high) {
+                        println!("string(boxed): ✅ SUCCESS - {}", report);
                         println!(
                             "🎯 BOXED string HANDLES {} LEVELS WHERE PURE string WOULD OVERFLOW!",
                             high_string_depth
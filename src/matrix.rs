@@ -0,0 +1,134 @@
+use crate::output;
+use crate::scenarios::ScenarioResult;
+use std::process::Command;
+
+// === รันทุก build profile ===
+// CRITICAL: Frame sizes differ massively between debug and release, so a number without
+// its build profile is meaningless. The matrix rebuilds this binary under each profile
+// (in its own target dir) and collects the `--scenarios --format csv` output of each.
+pub struct Profile {
+    pub name: &'static str,
+    pub release: bool,
+    pub config: &'static [&'static str], // `cargo --config` overrides
+    pub rustflags: &'static str,
+}
+
+pub const PROFILES: &[Profile] = &[
+    Profile {
+        name: "debug",
+        release: false,
+        config: &[],
+        rustflags: "",
+    },
+    Profile {
+        name: "release",
+        release: true,
+        config: &[],
+        rustflags: "",
+    },
+    Profile {
+        name: "opt-s",
+        release: true,
+        config: &["profile.release.opt-level=\"s\""],
+        rustflags: "",
+    },
+    Profile {
+        name: "opt-z",
+        release: true,
+        config: &["profile.release.opt-level=\"z\""],
+        rustflags: "",
+    },
+    Profile {
+        name: "lto",
+        release: true,
+        config: &["profile.release.lto=\"fat\""],
+        rustflags: "",
+    },
+    Profile {
+        name: "cgu1",
+        release: true,
+        config: &["profile.release.codegen-units=1"],
+        rustflags: "",
+    },
+    Profile {
+        name: "frame-pointers",
+        release: true,
+        config: &[],
+        rustflags: "-C force-frame-pointers=yes",
+    },
+    Profile {
+        name: "panic-abort",
+        release: true,
+        config: &["profile.release.panic=\"abort\""],
+        rustflags: "",
+    },
+];
+
+pub fn select_profiles(names: Option<&str>) -> Result<Vec<&'static Profile>, String> {
+    let Some(names) = names else {
+        return Ok(PROFILES.iter().collect());
+    };
+    names
+        .split(',')
+        .map(|name| {
+            PROFILES
+                .iter()
+                .find(|p| p.name == name)
+                .ok_or_else(|| format!("unknown profile '{}'", name))
+        })
+        .collect()
+}
+
+fn run_profile(profile: &Profile, child_args: &[String]) -> Result<Vec<ScenarioResult>, String> {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let mut cmd = Command::new(cargo);
+    cmd.arg("run")
+        .arg("--quiet")
+        .arg("--manifest-path")
+        .arg(format!("{}/Cargo.toml", manifest_dir))
+        .arg("--target-dir")
+        .arg(format!("{}/target/matrix/{}", manifest_dir, profile.name));
+    if profile.release {
+        cmd.arg("--release");
+    }
+    for config in profile.config {
+        cmd.arg("--config").arg(config);
+    }
+    if !profile.rustflags.is_empty() {
+        let existing = std::env::var("RUSTFLAGS").unwrap_or_default();
+        cmd.env("RUSTFLAGS", format!("{} {}", existing, profile.rustflags));
+    }
+    cmd.arg("--")
+        .arg("--scenarios")
+        .arg("--format")
+        .arg("csv")
+        .args(child_args);
+
+    let output = cmd
+        .output()
+        .map_err(|e| format!("failed to start cargo: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} ({})",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output::parse_csv(&String::from_utf8_lossy(&output.stdout)))
+}
+
+pub fn run_matrix(profiles: &[&Profile], child_args: &[String]) -> Vec<ScenarioResult> {
+    let mut results = Vec::new();
+    for profile in profiles {
+        eprintln!("matrix: building and running '{}' profile...", profile.name);
+        match run_profile(profile, child_args) {
+            Ok(rows) => results.extend(rows.into_iter().map(|mut r| {
+                r.profile = Some(profile.name.to_string());
+                r
+            })),
+            Err(e) => eprintln!("matrix: profile '{}' failed: {}", profile.name, e),
+        }
+    }
+    results
+}
@@ -0,0 +1,98 @@
+use std::fmt;
+use std::fs;
+use std::process::Command;
+
+// === ข้อมูล build และเครื่อง ===
+// CRITICAL: Attached to every result - per-level numbers are only comparable between runs
+// with the same compiler, target, optimization level and stack size.
+#[derive(Clone, Default)]
+pub struct Metadata(pub Vec<(String, String)>);
+
+pub const KEYS: &[&str] = &[
+    "rustc",
+    "target",
+    "profile",
+    "opt_level",
+    "debug_assertions",
+    "panic",
+    "rustflags",
+    "thread_stack_size",
+    "main_stack_limit",
+    "kernel",
+];
+
+impl Metadata {
+    pub fn get(&self, key: &str) -> &str {
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+            .unwrap_or("")
+    }
+}
+
+impl fmt::Display for Metadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in &self.0 {
+            writeln!(f, "{}: {}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+// IMPORTANT: thread_stack_size is the stack of the thread doing the measuring, which is
+// not necessarily the main thread's limit (None = measuring on the main thread itself)
+pub fn collect(thread_stack_size: Option<usize>) -> Metadata {
+    let main_limit = main_stack_limit();
+    let thread_stack_size = thread_stack_size.map_or_else(|| main_limit.clone(), |s| s.to_string());
+    let values = [
+        env!("BUILD_RUSTC_VERSION").to_string(),
+        env!("BUILD_TARGET").to_string(),
+        env!("BUILD_PROFILE").to_string(),
+        env!("BUILD_OPT_LEVEL").to_string(),
+        cfg!(debug_assertions).to_string(),
+        // CARGO_CFG_PANIC in build.rs does not see profile.*.panic, the cfg does
+        if cfg!(panic = "abort") {
+            "abort"
+        } else {
+            "unwind"
+        }
+        .to_string(),
+        env!("BUILD_RUSTFLAGS").to_string(),
+        thread_stack_size,
+        main_limit,
+        kernel_version(),
+    ];
+    Metadata(
+        KEYS.iter()
+            .zip(values)
+            .map(|(k, v)| (k.to_string(), v))
+            .collect(),
+    )
+}
+
+fn kernel_version() -> String {
+    if let Ok(release) = fs::read_to_string("/proc/sys/kernel/osrelease") {
+        return release.trim().to_string();
+    }
+    Command::new("uname")
+        .arg("-r")
+        .output()
+        .ok()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+// Soft RLIMIT_STACK of this process ("Max stack size  8388608  unlimited  bytes")
+fn main_stack_limit() -> String {
+    fs::read_to_string("/proc/self/limits")
+        .ok()
+        .and_then(|limits| {
+            limits
+                .lines()
+                .find(|line| line.starts_with("Max stack size"))
+                .and_then(|line| line.split_whitespace().nth(3).map(str::to_string))
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
@@ -0,0 +1,222 @@
+use crate::output::{self, Format};
+use crate::scenarios::{self, measure, Measurement, Scenario, ScenarioResult};
+use stacker::remaining_stack;
+use std::hint::black_box;
+
+// IMPORTANT: Every other family is self-recursive, so all levels have the same frame.
+// Here two or three functions call each other in a cycle. Each function pushes its own
+// sample, so the step between two samples is the frame of the function that pushed the
+// second one: a step of 0 means it was inlined into its caller (both pushes, one frame).
+
+// === even/odd แบบคลาสสิก (tail call) ===
+pub fn is_even_tracked(n: u64, stack_info: &mut Vec<usize>) -> bool {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n == 0 {
+        return true;
+    }
+    is_odd_tracked(n - 1, stack_info)
+}
+
+pub fn is_odd_tracked(n: u64, stack_info: &mut Vec<usize>) -> bool {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n == 0 {
+        return false;
+    }
+    is_even_tracked(n - 1, stack_info)
+}
+
+// === even/odd ที่นับจำนวนชั้น (ไม่ใช่ tail call) ===
+// CRITICAL: `1 + f(n - 1)` alone is rewritten into an accumulator loop in release builds.
+// Reading n through black_box after the call keeps it live, so every level keeps its frame.
+pub fn count_even_tracked(n: u64, stack_info: &mut Vec<usize>) -> u64 {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n == 0 {
+        return 0;
+    }
+    let below = count_odd_tracked(n - 1, stack_info);
+    below + 1 + black_box(n) - n
+}
+
+pub fn count_odd_tracked(n: u64, stack_info: &mut Vec<usize>) -> u64 {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n == 0 {
+        return 0;
+    }
+    let below = count_even_tracked(n - 1, stack_info);
+    below + 1 + black_box(n) - n
+}
+
+// === วงจร A -> B -> C -> A ที่ frame ขนาดต่างกัน ===
+// CRITICAL: Each local is read after the call, so it must live in the frame across it
+pub fn cycle_a_tracked(n: u64, stack_info: &mut Vec<usize>) -> u64 {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    let local = black_box([n as u8; 16]);
+    if n == 0 {
+        return 0;
+    }
+    cycle_b_tracked(n - 1, stack_info) + black_box(&local)[0] as u64
+}
+
+pub fn cycle_b_tracked(n: u64, stack_info: &mut Vec<usize>) -> u64 {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    let local = black_box([n as u8; 128]);
+    if n == 0 {
+        return 0;
+    }
+    cycle_c_tracked(n - 1, stack_info) + black_box(&local)[0] as u64
+}
+
+pub fn cycle_c_tracked(n: u64, stack_info: &mut Vec<usize>) -> u64 {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    let local = black_box([n as u8; 512]);
+    if n == 0 {
+        return 0;
+    }
+    cycle_a_tracked(n - 1, stack_info) + black_box(&local)[0] as u64
+}
+
+fn run_even_odd(n: u64) -> Measurement {
+    measure(
+        n,
+        || n,
+        |n, stack_info| {
+            black_box(is_even_tracked(*n, stack_info));
+        },
+    )
+}
+
+fn run_count_even_odd(n: u64) -> Measurement {
+    measure(
+        n,
+        || n,
+        |n, stack_info| {
+            black_box(count_even_tracked(*n, stack_info));
+        },
+    )
+}
+
+fn run_cycle(n: u64) -> Measurement {
+    measure(
+        n,
+        || n,
+        |n, stack_info| {
+            black_box(cycle_a_tracked(*n, stack_info));
+        },
+    )
+}
+
+pub const SCENARIOS: &[Scenario] = &[
+    Scenario {
+        name: "mutual-even-odd(tail)",
+        run: run_even_odd,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "mutual-even-odd(counted)",
+        run: run_count_even_odd,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "mutual-cycle(A16-B128-C512)",
+        run: run_cycle,
+        iterative: false,
+        requires_unwind: false,
+    },
+];
+
+// Functions of each cycle, in call order starting from the entry point
+const CYCLES: &[&[&str]] = &[&["even", "odd"], &["even", "odd"], &["A", "B", "C"]];
+
+// Mean step between consecutive samples, per function of the cycle
+fn frame_steps(stack_info: &[usize], functions: usize) -> Vec<f64> {
+    (0..functions)
+        .map(|f| {
+            let steps: Vec<usize> = stack_info
+                .windows(2)
+                .enumerate()
+                .filter(|(i, _)| (i + 1) % functions == f)
+                .map(|(_, pair)| pair[0].saturating_sub(pair[1]))
+                .collect();
+            steps.iter().sum::<usize>() as f64 / steps.len().max(1) as f64
+        })
+        .collect()
+}
+
+// === ตารางเทียบ frame ของแต่ละฟังก์ชันในวงจร ===
+// CRITICAL: The scenarios only keep the averaged report, so the table re-runs each one
+// that fit on the stack to read the raw samples
+pub fn render_table(format: Format, results: &[&ScenarioResult], stack_size: usize) -> String {
+    let rows: Vec<Vec<String>> = SCENARIOS
+        .iter()
+        .zip(CYCLES)
+        .filter_map(|(scenario, functions)| {
+            let result = results.iter().copied().find(|r| r.name == scenario.name)?;
+            let steps = result
+                .report
+                .as_ref()
+                .and_then(|_| scenarios::run_once(scenario, result.depth, stack_size))
+                .map(|m| frame_steps(&m.stack_info, functions.len()));
+            let frames = steps.as_ref().map_or("-".to_string(), |steps| {
+                functions
+                    .iter()
+                    .zip(steps)
+                    .map(|(f, step)| format!("{} {:.0}", f, step))
+                    .collect::<Vec<_>>()
+                    .join(" / ")
+            });
+            // All steps 0 is a loop (tail-call eliminated), not inlining
+            let inlined = steps.as_ref().map_or("-".to_string(), |steps| {
+                let notes: Vec<String> = steps
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &step)| step == 0.0)
+                    .map(|(i, _)| {
+                        let caller = functions[(i + functions.len() - 1) % functions.len()];
+                        format!("{} into {}", functions[i], caller)
+                    })
+                    .collect();
+                if notes.len() == steps.len() {
+                    "-".to_string()
+                } else if notes.is_empty() {
+                    "none".to_string()
+                } else {
+                    notes.join(", ")
+                }
+            });
+            Some(vec![
+                scenario.name.to_string(),
+                output::per_level_cell(Some(result)),
+                frames,
+                inlined,
+            ])
+        })
+        .collect();
+    let depth = results.first().map_or(0, |r| r.depth);
+    output::render_grid(
+        format,
+        &format!("Mutual recursion: frame per function (depth {})", depth),
+        &[
+            "Scenario",
+            "Avg B/lvl",
+            "Frame per function (bytes)",
+            "Inlined",
+        ],
+        &rows,
+    )
+}
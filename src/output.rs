@@ -0,0 +1,680 @@
+use crate::metadata::{self, Metadata};
+use crate::scenarios::{PhaseTiming, ScenarioResult};
+use crate::stats::{RunStats, Stats};
+use crate::StackReport;
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+// === รูปแบบผลลัพธ์ ===
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Csv,
+    Json,
+    Markdown,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            "markdown" | "md" => Ok(Format::Markdown),
+            other => Err(format!(
+                "unknown format '{}' (expected text, csv, json or markdown)",
+                other
+            )),
+        }
+    }
+}
+
+pub fn render(format: Format, results: &[ScenarioResult]) -> String {
+    match format {
+        Format::Text => render_text(results),
+        Format::Csv => render_csv(results),
+        Format::Json => render_json(results),
+        Format::Markdown => render_markdown(results),
+    }
+}
+
+// IMPORTANT: Results that carry a build profile (matrix mode) are grouped into one
+// comparison table per scenario; plain runs stay a single flat table
+fn grouped(results: &[ScenarioResult]) -> Vec<(&str, u64, Vec<&ScenarioResult>)> {
+    let mut groups: Vec<(&str, u64, Vec<&ScenarioResult>)> = Vec::new();
+    for r in results {
+        match groups
+            .iter_mut()
+            .find(|(name, depth, _)| *name == r.name && *depth == r.depth)
+        {
+            Some((_, _, rows)) => rows.push(r),
+            None => groups.push((&r.name, r.depth, vec![r])),
+        }
+    }
+    groups
+}
+
+// IMPORTANT: One entry per distinct build - a single run has one, the matrix one per profile
+fn builds(results: &[ScenarioResult]) -> Vec<(&str, &Metadata)> {
+    let mut builds: Vec<(&str, &Metadata)> = Vec::new();
+    for r in results {
+        let label = r.profile.as_deref().unwrap_or("this build");
+        if !builds.iter().any(|(l, _)| *l == label) {
+            builds.push((label, &r.metadata));
+        }
+    }
+    builds
+}
+
+fn has_profiles(results: &[ScenarioResult]) -> bool {
+    results.iter().any(|r| r.profile.is_some())
+}
+
+// IMPORTANT: "tail-call" is reserved for recursion the optimizer turned into a loop. A
+// scenario that loops by design (Scenario::iterative) is flat no matter what it compiles to.
+fn note(r: &ScenarioResult, report: &StackReport) -> &'static str {
+    if r.iterative {
+        "flat (by design)"
+    } else if report.tail_call_eliminated {
+        "tail-call eliminated"
+    } else {
+        ""
+    }
+}
+
+// IMPORTANT: Extra statistics appended to a line when the scenario was repeated
+fn stats_summary(stats: &RunStats) -> String {
+    let mut out = format!(
+        "{} runs: per level mean {:.2} median {:.2} sd {:.2} [{:.2}..{:.2}], time mean {:.0} ns median {:.0} ns sd {:.0} [{:.0}..{:.0}]",
+        stats.runs,
+        stats.per_level.mean,
+        stats.per_level.median,
+        stats.per_level.stddev,
+        stats.per_level.min,
+        stats.per_level.max,
+        stats.time_ns.mean,
+        stats.time_ns.median,
+        stats.time_ns.stddev,
+        stats.time_ns.min,
+        stats.time_ns.max
+    );
+    let outliers = stats.per_level.outliers + stats.time_ns.outliers;
+    if outliers > 0 {
+        let _ = write!(out, ", {} outliers dropped", outliers);
+    }
+    if stats.disagree {
+        out.push_str(" [runs disagree]");
+    }
+    out
+}
+
+fn timing_summary(timing: &PhaseTiming) -> String {
+    format!(
+        "build {:.1} ns/level, eval {:.1} ns/level, drop {:.1} ns/level",
+        timing.build_ns_per_level, timing.eval_ns_per_level, timing.drop_ns_per_level
+    )
+}
+
+fn status_text(r: &ScenarioResult) -> String {
+    match (&r.report, &r.skipped) {
+        (Some(report), _) if r.iterative => format!(
+            "{} bytes ({:.2} per level) [{}]",
+            report.used,
+            report.per_level,
+            note(r, report)
+        ),
+        (Some(report), _) => report.to_string(),
+        (None, Some(reason)) => format!("skipped: {}", reason),
+        (None, None) => "failed".to_string(),
+    }
+}
+
+fn text_line(r: &ScenarioResult) -> String {
+    let mut line = status_text(r);
+    if let Some(timing) = &r.timing {
+        let _ = write!(line, " | {}", timing_summary(timing));
+    }
+    if let Some(stats) = &r.stats {
+        let _ = write!(line, " | {}", stats_summary(stats));
+    }
+    line
+}
+
+fn render_text(results: &[ScenarioResult]) -> String {
+    let mut out = String::new();
+    for (label, metadata) in builds(results) {
+        let _ = writeln!(out, "=== Build & environment ({}) ===", label);
+        let _ = writeln!(out, "{}", metadata);
+    }
+    if has_profiles(results) {
+        for (name, depth, rows) in grouped(results) {
+            let _ = writeln!(out, "\n=== {} (depth {}) ===", name, depth);
+            for r in rows {
+                let profile = r.profile.as_deref().unwrap_or("-");
+                let _ = writeln!(out, "{:<16} {}", profile, text_line(r));
+            }
+        }
+        return out;
+    }
+    for r in results {
+        let _ = writeln!(out, "{} (depth {}): {}", r.name, r.depth, text_line(r));
+    }
+    out
+}
+
+// === ตาราง CSV / JSON ===
+enum Cell {
+    Text(String),
+    Number(String), // Written unquoted (numbers and booleans)
+    Empty,
+}
+
+fn number(value: impl fmt::Display) -> Cell {
+    Cell::Number(value.to_string())
+}
+
+fn stat_cells(fields: &mut Vec<(String, Cell)>, prefix: &str, stats: Option<&Stats>) {
+    let values = stats.map(|s| [s.mean, s.median, s.stddev, s.min, s.max]);
+    for (i, name) in ["mean", "median", "stddev", "min", "max"]
+        .iter()
+        .enumerate()
+    {
+        let cell = values.map_or(Cell::Empty, |v| number(format!("{:.2}", v[i])));
+        fields.push((format!("{}_{}", prefix, name), cell));
+    }
+    let outliers = stats.map_or(Cell::Empty, |s| number(s.outliers));
+    fields.push((format!("{}_outliers", prefix), outliers));
+}
+
+// CRITICAL: The single flat view of a result shared by CSV and JSON (and read back by
+// parse_csv), so both formats always carry the same columns
+fn fields(r: &ScenarioResult, with_stats: bool) -> Vec<(String, Cell)> {
+    let mut fields = Vec::new();
+    if let Some(profile) = &r.profile {
+        fields.push(("profile".to_string(), Cell::Text(profile.clone())));
+    }
+    fields.push(("scenario".to_string(), Cell::Text(r.name.clone())));
+    fields.push(("depth".to_string(), number(r.depth)));
+    let status = match (&r.report, &r.skipped) {
+        (Some(_), _) => "ok",
+        (None, Some(_)) => "skipped",
+        (None, None) => "failed",
+    };
+    fields.push(("status".to_string(), Cell::Text(status.to_string())));
+    let note = r.skipped.clone().map_or(Cell::Empty, Cell::Text);
+    fields.push(("note".to_string(), note));
+    let report = r.report.as_ref();
+    fields.push((
+        "levels".to_string(),
+        report.map_or(Cell::Empty, |x| number(x.levels)),
+    ));
+    fields.push((
+        "used_bytes".to_string(),
+        report.map_or(Cell::Empty, |x| number(x.used)),
+    ));
+    fields.push((
+        "per_level".to_string(),
+        report.map_or(Cell::Empty, |x| number(format!("{:.2}", x.per_level))),
+    ));
+    fields.push((
+        "tail_call_eliminated".to_string(),
+        report.map_or(Cell::Empty, |x| number(x.tail_call_eliminated)),
+    ));
+    fields.push(("iterative".to_string(), number(r.iterative)));
+    let timing = r.timing.as_ref();
+    for (name, value) in [
+        ("build_ns_per_level", timing.map(|t| t.build_ns_per_level)),
+        ("eval_ns_per_level", timing.map(|t| t.eval_ns_per_level)),
+        ("drop_ns_per_level", timing.map(|t| t.drop_ns_per_level)),
+    ] {
+        let cell = value.map_or(Cell::Empty, |v| number(format!("{:.2}", v)));
+        fields.push((name.to_string(), cell));
+    }
+    if with_stats {
+        let stats = r.stats.as_ref();
+        fields.push((
+            "runs".to_string(),
+            stats.map_or(Cell::Empty, |s| number(s.runs)),
+        ));
+        stat_cells(&mut fields, "per_level", stats.map(|s| &s.per_level));
+        stat_cells(&mut fields, "time_ns", stats.map(|s| &s.time_ns));
+        fields.push((
+            "runs_disagree".to_string(),
+            stats.map_or(Cell::Empty, |s| number(s.disagree)),
+        ));
+    }
+    fields
+}
+
+fn has_stats(results: &[ScenarioResult]) -> bool {
+    results.iter().any(|r| r.stats.is_some())
+}
+
+fn render_csv(results: &[ScenarioResult]) -> String {
+    let with_stats = has_stats(results);
+    let mut out = String::new();
+    for (i, r) in results.iter().enumerate() {
+        let fields = fields(r, with_stats);
+        if i == 0 {
+            let names: Vec<&str> = fields.iter().map(|(name, _)| name.as_str()).collect();
+            out.push_str(&names.join(","));
+            for key in metadata::KEYS {
+                let _ = write!(out, ",{}", key);
+            }
+            out.push('\n');
+        }
+        let cells: Vec<String> = fields
+            .iter()
+            .map(|(_, cell)| match cell {
+                Cell::Text(s) => csv_field(s),
+                Cell::Number(s) => s.clone(),
+                Cell::Empty => String::new(),
+            })
+            .collect();
+        out.push_str(&cells.join(","));
+        for key in metadata::KEYS {
+            let _ = write!(out, ",{}", csv_field(r.metadata.get(key)));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn render_json(results: &[ScenarioResult]) -> String {
+    let with_stats = has_stats(results);
+    let mut out = String::from("[\n");
+    for (i, r) in results.iter().enumerate() {
+        out.push_str("  {");
+        for (name, cell) in fields(r, with_stats) {
+            let value = match cell {
+                Cell::Text(s) => json_string(&s),
+                Cell::Number(s) => s,
+                Cell::Empty => "null".to_string(),
+            };
+            let _ = write!(out, "{}: {}, ", json_string(&name), value);
+        }
+        out.push_str("\"metadata\": {");
+        for (i, (key, value)) in r.metadata.0.iter().enumerate() {
+            if i > 0 {
+                out.push_str(", ");
+            }
+            let _ = write!(out, "{}: {}", json_string(key), json_string(value));
+        }
+        out.push_str("}}");
+        if i + 1 < results.len() {
+            out.push(',');
+        }
+        out.push('\n');
+    }
+    out.push_str("]\n");
+    out
+}
+
+const MARKDOWN_PROFILE_HEADER: &str = "\
+| Profile | Levels | Used (bytes) | Per level | Build ns/level | Eval ns/level | Drop ns/level | Note |
+|---------|--------|--------------|-----------|----------------|---------------|---------------|------|
+";
+
+const MARKDOWN_SCENARIO_HEADER: &str = "\
+| Scenario | Levels | Used (bytes) | Per level | Build ns/level | Eval ns/level | Drop ns/level | Note |
+|----------|--------|--------------|-----------|----------------|---------------|---------------|------|
+";
+
+fn markdown_row(out: &mut String, first: &str, r: &ScenarioResult) {
+    let mut note = match &r.report {
+        Some(report) => note(r, report).to_string(),
+        None => status_text(r),
+    };
+    if let Some(stats) = &r.stats {
+        if !note.is_empty() {
+            note.push_str("; ");
+        }
+        note.push_str(&stats_summary(stats));
+    }
+    let timing = match &r.timing {
+        Some(t) => format!(
+            "{:.1} | {:.1} | {:.1}",
+            t.build_ns_per_level, t.eval_ns_per_level, t.drop_ns_per_level
+        ),
+        None => "- | - | -".to_string(),
+    };
+    match &r.report {
+        Some(report) => {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {:.2} | {} | {} |",
+                first, report.levels, report.used, report.per_level, timing, note
+            );
+        }
+        None => {
+            let _ = writeln!(out, "| {} | - | - | - | {} | {} |", first, timing, note);
+        }
+    }
+}
+
+fn render_markdown(results: &[ScenarioResult]) -> String {
+    let mut out = String::new();
+    let builds = builds(results);
+    out.push_str("### Build & environment\n\n| Key |");
+    for (label, _) in &builds {
+        let _ = write!(out, " {} |", label);
+    }
+    out.push_str("\n|-----|");
+    for _ in &builds {
+        out.push_str("-----|");
+    }
+    out.push('\n');
+    for key in metadata::KEYS {
+        let _ = write!(out, "| {} |", key);
+        for (_, metadata) in &builds {
+            let _ = write!(out, " {} |", metadata.get(key));
+        }
+        out.push('\n');
+    }
+    out.push('\n');
+    if has_profiles(results) {
+        for (name, depth, rows) in grouped(results) {
+            let _ = writeln!(out, "### {} (depth {})\n", name, depth);
+            out.push_str(MARKDOWN_PROFILE_HEADER);
+            for r in rows {
+                markdown_row(&mut out, r.profile.as_deref().unwrap_or("-"), r);
+            }
+            out.push('\n');
+        }
+        return out;
+    }
+    out.push_str(MARKDOWN_SCENARIO_HEADER);
+    for r in results {
+        markdown_row(&mut out, &format!("{} (depth {})", r.name, r.depth), r);
+    }
+    out
+}
+
+// IMPORTANT: Shared layout for the per-family comparison tables (payload curve, alignment,
+// passing conventions, ...): one row per variant, one column per measurement
+pub fn render_grid(format: Format, title: &str, columns: &[&str], rows: &[Vec<String>]) -> String {
+    let mut out = String::new();
+    if format == Format::Markdown {
+        let _ = writeln!(out, "### {}\n", title);
+        let _ = writeln!(out, "| {} |", columns.join(" | "));
+        let _ = writeln!(
+            out,
+            "|{}|",
+            columns
+                .iter()
+                .map(|c| "-".repeat(c.len() + 2))
+                .collect::<Vec<_>>()
+                .join("|")
+        );
+        for row in rows {
+            let _ = writeln!(out, "| {} |", row.join(" | "));
+        }
+        return out;
+    }
+    let widths: Vec<usize> = (0..columns.len())
+        .map(|i| {
+            rows.iter()
+                .filter_map(|row| row.get(i))
+                .map(String::len)
+                .chain([columns[i].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let header: Vec<String> = columns.iter().map(|c| c.to_string()).collect();
+    let _ = writeln!(out, "=== {} ===", title);
+    for row in std::iter::once(&header).chain(rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, &w))| {
+                if i == 0 {
+                    format!("{:<w$}", cell)
+                } else {
+                    format!("{:>w$}", cell)
+                }
+            })
+            .collect();
+        let _ = writeln!(out, "{}", line.join("  "));
+    }
+    out
+}
+
+// Per-level bytes of one scenario as a grid cell
+pub fn per_level_cell(result: Option<&ScenarioResult>) -> String {
+    match result {
+        Some(r) => match (&r.report, &r.skipped) {
+            (Some(_), _) if r.iterative => "flat (by design)".to_string(),
+            (Some(report), _) if report.tail_call_eliminated => "tail-call".to_string(),
+            (Some(report), _) => format!("{:.2}", report.per_level),
+            (None, Some(_)) => "skipped".to_string(),
+            (None, None) => "failed".to_string(),
+        },
+        None => "-".to_string(),
+    }
+}
+
+// IMPORTANT: Inverse of render_csv for un-profiled output - used by the matrix runner to
+// read back what each child build measured, including the child's own build metadata
+pub fn parse_csv(input: &str) -> Vec<ScenarioResult> {
+    let mut lines = input
+        .lines()
+        .skip_while(|line| !line.starts_with("scenario,"));
+    let Some(header) = lines.next() else {
+        return Vec::new();
+    };
+    let header = split_csv_line(header);
+    lines
+        .filter_map(|line| {
+            let cols = split_csv_line(line);
+            let get = |name: &str| {
+                header
+                    .iter()
+                    .position(|h| h == name)
+                    .and_then(|i| cols.get(i))
+                    .map(String::as_str)
+                    .unwrap_or("")
+            };
+            let report = if get("status") == "ok" {
+                Some(StackReport {
+                    levels: get("levels").parse().ok()?,
+                    used: get("used_bytes").parse().ok()?,
+                    per_level: get("per_level").parse().ok()?,
+                    tail_call_eliminated: get("tail_call_eliminated") == "true",
+                })
+            } else {
+                None
+            };
+            let timing = (|| {
+                Some(PhaseTiming {
+                    build_ns_per_level: get("build_ns_per_level").parse().ok()?,
+                    eval_ns_per_level: get("eval_ns_per_level").parse().ok()?,
+                    drop_ns_per_level: get("drop_ns_per_level").parse().ok()?,
+                })
+            })();
+            let stat = |prefix: &str| -> Option<Stats> {
+                let value = |name: &str| get(&format!("{}_{}", prefix, name)).parse().ok();
+                Some(Stats {
+                    mean: value("mean")?,
+                    median: value("median")?,
+                    stddev: value("stddev")?,
+                    min: value("min")?,
+                    max: value("max")?,
+                    outliers: get(&format!("{}_outliers", prefix)).parse().ok()?,
+                })
+            };
+            let stats = get("runs").parse().ok().and_then(|runs| {
+                Some(RunStats {
+                    runs,
+                    per_level: stat("per_level")?,
+                    time_ns: stat("time_ns")?,
+                    disagree: get("runs_disagree") == "true",
+                })
+            });
+            let metadata = Metadata(
+                metadata::KEYS
+                    .iter()
+                    .map(|key| (key.to_string(), get(key).to_string()))
+                    .collect(),
+            );
+            Some(ScenarioResult {
+                profile: None,
+                name: get("scenario").to_string(),
+                depth: get("depth").parse().ok()?,
+                iterative: get("iterative") == "true",
+                report,
+                skipped: (get("status") == "skipped").then(|| get("note").to_string()),
+                timing,
+                stats,
+                metadata,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(name: &str, report: Option<StackReport>, skipped: Option<&str>) -> ScenarioResult {
+        ScenarioResult {
+            profile: None,
+            name: name.to_string(),
+            depth: 1000,
+            iterative: false,
+            timing: report.as_ref().map(|_| PhaseTiming {
+                build_ns_per_level: 40.25,
+                eval_ns_per_level: 8.5,
+                drop_ns_per_level: 19.75,
+            }),
+            report,
+            skipped: skipped.map(str::to_string),
+            stats: None,
+            metadata: metadata::collect(Some(8 << 20)),
+        }
+    }
+
+    fn report(tail_call_eliminated: bool) -> StackReport {
+        StackReport {
+            used: 48000,
+            per_level: 47.95,
+            levels: 1001,
+            tail_call_eliminated,
+        }
+    }
+
+    #[test]
+    fn csv_round_trips_through_parse_csv() {
+        let mut flat = result("iter-flat(BoxedFact)", Some(report(true)), None);
+        flat.iterative = true;
+        let results = vec![
+            result("boxed(u64)", Some(report(false)), None),
+            flat,
+            // Quoted because of the comma
+            result("boxed(u128)", None, Some("needs ~1, of 2 bytes")),
+            result("failed(u8)", None, None),
+        ];
+        let parsed = parse_csv(&render(Format::Csv, &results));
+        assert_eq!(parsed.len(), results.len());
+        for (want, got) in results.iter().zip(&parsed) {
+            assert_eq!(got.name, want.name);
+            assert_eq!(got.depth, want.depth);
+            assert_eq!(got.iterative, want.iterative);
+            assert_eq!(got.skipped, want.skipped);
+            assert_eq!(got.metadata.0, want.metadata.0);
+            assert!(got.stats.is_none());
+            match (&want.report, &got.report) {
+                (Some(want), Some(got)) => {
+                    assert_eq!(got.used, want.used);
+                    assert_eq!(got.levels, want.levels);
+                    assert_eq!(got.per_level, want.per_level);
+                    assert_eq!(got.tail_call_eliminated, want.tail_call_eliminated);
+                }
+                (None, None) => {}
+                _ => panic!("{}: report lost or invented", want.name),
+            }
+            let timing = |r: &ScenarioResult| {
+                r.timing.as_ref().map(|t| {
+                    (
+                        t.build_ns_per_level,
+                        t.eval_ns_per_level,
+                        t.drop_ns_per_level,
+                    )
+                })
+            };
+            assert_eq!(timing(got), timing(want), "{}", want.name);
+        }
+    }
+
+    #[test]
+    fn csv_round_trips_run_statistics() {
+        let mut repeated = result("simple(u64)", Some(report(false)), None);
+        repeated.stats = RunStats::from_runs(&[47.95, 47.95, 48.0], &[100.0, 110.0, 105.0], 0.1);
+        let parsed = parse_csv(&render(Format::Csv, &[repeated]));
+        let stats = parsed[0].stats.as_ref().expect("stats columns parsed back");
+        assert_eq!(stats.runs, 3);
+        assert_eq!(stats.per_level.median, 47.95);
+        assert_eq!(stats.time_ns.mean, 105.0);
+        assert!(!stats.disagree);
+    }
+
+    #[test]
+    fn parse_csv_skips_preamble_and_ignores_garbage() {
+        let csv = render(
+            Format::Csv,
+            &[result("simple(u8)", Some(report(false)), None)],
+        );
+        let noisy = format!("Compiling test_fact\n{}not,a,number\n", csv);
+        let parsed = parse_csv(&noisy);
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].name, "simple(u8)");
+        assert!(parse_csv("no csv here").is_empty());
+    }
+}
@@ -0,0 +1,290 @@
+use crate::scenarios::{measure, Measurement, Scenario};
+use stacker::remaining_stack;
+use std::hint::black_box;
+
+// IMPORTANT: Real overflows come from parsing deeply nested input, not from factorials.
+// Grammar:  expr := term (('+' | '-') term)*
+//           term := atom ('*' atom)*
+//           atom := number | '(' expr ')'
+// One nesting level costs THREE frames (expr -> term -> atom); only parse_expr samples,
+// so the per-level figure is per nesting level. The heap-stack version keeps its pending
+// operators and values in Vecs and has a constant stack - it reports 0 bytes/level.
+
+// Generated input with `depth` nested parentheses: "((1)*2+1)*2+1" for depth 2
+pub fn nested_expression(depth: u64) -> String {
+    let mut input = String::with_capacity(depth as usize * 7 + 1);
+    input.extend((0..depth).map(|_| '('));
+    input.push('1');
+    input.extend((0..depth).map(|_| ")*2+1"));
+    input
+}
+
+// What nested_expression(depth) evaluates to: v = v * 2 + 1 per level, so 2^(depth+1) - 1
+// until it wraps like the parsers do
+pub fn nested_value(depth: u64) -> i64 {
+    (0..depth).fold(1i64, |v, _| v.wrapping_mul(2).wrapping_add(1))
+}
+
+// === recursive descent (stack) ===
+pub struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Parser {
+            input: input.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    pub fn parse(mut self, stack_info: &mut Vec<usize>) -> Result<i64, String> {
+        let value = self.parse_expr(stack_info)?;
+        match self.peek() {
+            None => Ok(value),
+            Some(c) => Err(format!("unexpected '{}' at {}", c as char, self.pos)),
+        }
+    }
+
+    fn parse_expr(&mut self, stack_info: &mut Vec<usize>) -> Result<i64, String> {
+        if let Some(rem) = remaining_stack() {
+            stack_info.push(rem); // One sample per nesting level
+        }
+        let mut acc = self.parse_term(stack_info)?;
+        loop {
+            match self.peek() {
+                Some(b'+') => {
+                    self.pos += 1;
+                    acc = acc.wrapping_add(self.parse_term(stack_info)?);
+                }
+                Some(b'-') => {
+                    self.pos += 1;
+                    acc = acc.wrapping_sub(self.parse_term(stack_info)?);
+                }
+                _ => return Ok(acc),
+            }
+        }
+    }
+
+    fn parse_term(&mut self, stack_info: &mut Vec<usize>) -> Result<i64, String> {
+        let mut acc = self.parse_atom(stack_info)?;
+        while self.peek() == Some(b'*') {
+            self.pos += 1;
+            acc = acc.wrapping_mul(self.parse_atom(stack_info)?);
+        }
+        Ok(acc)
+    }
+
+    fn parse_atom(&mut self, stack_info: &mut Vec<usize>) -> Result<i64, String> {
+        match self.peek() {
+            Some(b'(') => {
+                self.pos += 1;
+                let value = self.parse_expr(stack_info)?;
+                if self.peek() != Some(b')') {
+                    return Err(format!("expected ')' at {}", self.pos));
+                }
+                self.pos += 1;
+                Ok(value)
+            }
+            Some(c) if c.is_ascii_digit() => Ok(self.parse_number()),
+            Some(c) => Err(format!("unexpected '{}' at {}", c as char, self.pos)),
+            None => Err("unexpected end of input".to_string()),
+        }
+    }
+
+    fn parse_number(&mut self) -> i64 {
+        let mut value: i64 = 0;
+        while let Some(c) = self.peek().filter(u8::is_ascii_digit) {
+            value = value.wrapping_mul(10).wrapping_add((c - b'0') as i64);
+            self.pos += 1;
+        }
+        value
+    }
+}
+
+// === iterative + heap stack (shunting-yard) ===
+fn precedence(op: u8) -> u8 {
+    match op {
+        b'*' => 2,
+        b'+' | b'-' => 1,
+        _ => 0, // '(' never gets applied by precedence
+    }
+}
+
+fn apply(values: &mut Vec<i64>, op: u8) -> Result<(), String> {
+    let (Some(rhs), Some(lhs)) = (values.pop(), values.pop()) else {
+        return Err(format!("missing operand for '{}'", op as char));
+    };
+    values.push(match op {
+        b'+' => lhs.wrapping_add(rhs),
+        b'-' => lhs.wrapping_sub(rhs),
+        _ => lhs.wrapping_mul(rhs),
+    });
+    Ok(())
+}
+
+// CRITICAL: Same grammar and same samples (one per '(' plus the outermost expr), but the
+// pending work lives in two Vecs on the heap - nesting only grows the Vecs
+pub fn parse_heap_stack_tracked(input: &str, stack_info: &mut Vec<usize>) -> Result<i64, String> {
+    let mut values: Vec<i64> = Vec::new();
+    let mut ops: Vec<u8> = Vec::new();
+    let bytes = input.as_bytes();
+    let mut pos = 0;
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    while let Some(&c) = bytes.get(pos) {
+        match c {
+            b'(' => {
+                if let Some(rem) = remaining_stack() {
+                    stack_info.push(rem);
+                }
+                ops.push(c);
+                pos += 1;
+            }
+            b')' => {
+                loop {
+                    match ops.pop() {
+                        Some(b'(') => break,
+                        Some(op) => apply(&mut values, op)?,
+                        None => return Err(format!("unmatched ')' at {}", pos)),
+                    }
+                }
+                pos += 1;
+            }
+            b'+' | b'-' | b'*' => {
+                while let Some(&top) = ops.last() {
+                    if precedence(top) < precedence(c) {
+                        break;
+                    }
+                    ops.pop();
+                    apply(&mut values, top)?;
+                }
+                ops.push(c);
+                pos += 1;
+            }
+            c if c.is_ascii_digit() => {
+                let mut value: i64 = 0;
+                while let Some(&d) = bytes.get(pos).filter(|d| d.is_ascii_digit()) {
+                    value = value.wrapping_mul(10).wrapping_add((d - b'0') as i64);
+                    pos += 1;
+                }
+                values.push(value);
+            }
+            c => return Err(format!("unexpected '{}' at {}", c as char, pos)),
+        }
+    }
+    while let Some(op) = ops.pop() {
+        if op == b'(' {
+            return Err("unclosed '('".to_string());
+        }
+        apply(&mut values, op)?;
+    }
+    match values.as_slice() {
+        [value] => Ok(*value),
+        _ => Err("malformed expression".to_string()),
+    }
+}
+
+// IMPORTANT: A parse error or a wrong value panics, so the run reports "failed" instead of
+// timing a parser that gave up early
+fn run_recursive(n: u64) -> Measurement {
+    measure(
+        n,
+        || (nested_expression(n), nested_value(n)),
+        |(input, expected), stack_info| {
+            let value = black_box(Parser::new(input).parse(stack_info));
+            assert_eq!(value, Ok(*expected), "recursive parse of depth {}", n);
+        },
+    )
+}
+
+fn run_heap_stack(n: u64) -> Measurement {
+    measure(
+        n,
+        || (nested_expression(n), nested_value(n)),
+        |(input, expected), stack_info| {
+            let value = black_box(parse_heap_stack_tracked(input, stack_info));
+            assert_eq!(value, Ok(*expected), "heap-stack parse of depth {}", n);
+        },
+    )
+}
+
+pub const SCENARIOS: &[Scenario] = &[
+    Scenario {
+        name: "parser-expr(recursive)",
+        run: run_recursive,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "parser-expr(heap stack)",
+        run: run_heap_stack,
+        iterative: true,
+        requires_unwind: false,
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn both(input: &str) -> (Result<i64, String>, Result<i64, String>) {
+        let recursive = Parser::new(input).parse(&mut Vec::new());
+        let heap_stack = parse_heap_stack_tracked(input, &mut Vec::new());
+        (recursive, heap_stack)
+    }
+
+    #[test]
+    fn parsers_agree_on_values() {
+        for (input, expected) in [
+            ("7", 7),
+            ("2+3*4", 14),
+            ("(2+3)*4", 20),
+            ("10-4-3", 3),
+            ("2*3*4-5", 19),
+            ("((1)*2+1)*2+1", 7),
+        ] {
+            assert_eq!(both(input), (Ok(expected), Ok(expected)), "{}", input);
+        }
+    }
+
+    #[test]
+    fn parsers_agree_on_nested_input() {
+        for depth in [0, 1, 2, 10, 62, 63, 64, 100] {
+            let input = nested_expression(depth);
+            let expected = Ok(nested_value(depth));
+            assert_eq!(
+                both(&input),
+                (expected.clone(), expected),
+                "depth {}",
+                depth
+            );
+        }
+    }
+
+    #[test]
+    fn parsers_both_reject_malformed_input() {
+        for input in ["", "(1", "1)", "1+", "*2", "1+a"] {
+            let (recursive, heap_stack) = both(input);
+            assert!(recursive.is_err(), "recursive accepted {:?}", input);
+            assert!(heap_stack.is_err(), "heap stack accepted {:?}", input);
+        }
+    }
+
+    #[test]
+    fn one_sample_per_nesting_level() {
+        let input = nested_expression(5);
+        let mut recursive = Vec::new();
+        let mut heap_stack = Vec::new();
+        Parser::new(&input).parse(&mut recursive).unwrap();
+        parse_heap_stack_tracked(&input, &mut heap_stack).unwrap();
+        assert_eq!(recursive.len(), 6);
+        assert_eq!(heap_stack.len(), 6);
+    }
+}
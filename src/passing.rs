@@ -0,0 +1,181 @@
+use crate::output::{self, Format};
+use crate::scenarios::{measure, Measurement, Scenario, ScenarioResult};
+use stacker::remaining_stack;
+use std::hint::black_box;
+
+// IMPORTANT: simple_factorial_tracked_u128 passes its payload by value in every frame.
+// This family keeps the struct and the recursion identical and varies ONLY how the struct
+// reaches the next level: by value, &T, Box<T> or &mut T.
+
+#[derive(Clone, Copy)]
+pub struct Record<const N: usize>([u8; N]);
+
+impl<const N: usize> Record<N> {
+    fn new() -> Self {
+        Record([1; N])
+    }
+}
+
+// === ส่งแบบ by value (copy ทุกชั้น) ===
+pub fn by_value_tracked<const N: usize>(n: u64, record: Record<N>, stack_info: &mut Vec<usize>) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n > 0 {
+        by_value_tracked(n - 1, black_box(record), stack_info);
+    }
+}
+
+// === ส่งแบบ &T ===
+pub fn by_ref_tracked<const N: usize>(n: u64, record: &Record<N>, stack_info: &mut Vec<usize>) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n > 0 {
+        by_ref_tracked(n - 1, black_box(record), stack_info);
+    }
+}
+
+// === ส่งแบบ Box<T> (move pointer ลงไป) ===
+pub fn by_box_tracked<const N: usize>(n: u64, record: Box<Record<N>>, stack_info: &mut Vec<usize>) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n > 0 {
+        by_box_tracked(n - 1, black_box(record), stack_info);
+    }
+}
+
+// === ส่งแบบ &mut T ===
+pub fn by_mut_tracked<const N: usize>(n: u64, record: &mut Record<N>, stack_info: &mut Vec<usize>) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if let Some(first) = record.0.first_mut() {
+        *first = n as u8; // Actually use the mutable access
+    }
+    if n > 0 {
+        by_mut_tracked(n - 1, black_box(&mut *record), stack_info);
+    }
+}
+
+fn run_by_value<const N: usize>(n: u64) -> Measurement {
+    measure(n, Record::<N>::new, |record, stack_info| {
+        by_value_tracked(n, *record, stack_info)
+    })
+}
+
+fn run_by_ref<const N: usize>(n: u64) -> Measurement {
+    measure(n, Record::<N>::new, |record, stack_info| {
+        by_ref_tracked(n, record, stack_info)
+    })
+}
+
+fn run_by_box<const N: usize>(n: u64) -> Measurement {
+    // The Box travels down the recursion, so build hands over an Option to take it from
+    measure(
+        n,
+        || Some(Box::new(Record::<N>::new())),
+        |record, stack_info| {
+            if let Some(record) = record.take() {
+                by_box_tracked(n, record, stack_info)
+            }
+        },
+    )
+}
+
+fn run_by_mut<const N: usize>(n: u64) -> Measurement {
+    measure(n, Record::<N>::new, |record, stack_info| {
+        by_mut_tracked(n, record, stack_info)
+    })
+}
+
+pub const CONVENTIONS: &[&str] = &["value", "ref", "box", "mut"];
+
+macro_rules! passing_sizes {
+    ($($bytes:literal),*) => {
+        pub const SIZES: &[usize] = &[$($bytes),*];
+
+        pub const SCENARIOS: &[Scenario] = &[$(
+            Scenario {
+                name: concat!("passing-value(", $bytes, "B)"),
+                run: run_by_value::<$bytes>,
+                iterative: false,
+                requires_unwind: false,
+            },
+            Scenario {
+                name: concat!("passing-ref(", $bytes, "B)"),
+                run: run_by_ref::<$bytes>,
+                iterative: false,
+                requires_unwind: false,
+            },
+            Scenario {
+                name: concat!("passing-box(", $bytes, "B)"),
+                run: run_by_box::<$bytes>,
+                iterative: false,
+                requires_unwind: false,
+            },
+            Scenario {
+                name: concat!("passing-mut(", $bytes, "B)"),
+                run: run_by_mut::<$bytes>,
+                iterative: false,
+                requires_unwind: false,
+            },
+        )*];
+    };
+}
+
+passing_sizes!(8, 16, 32, 64, 128, 256, 512, 1024, 2048);
+
+pub fn scenario_name(convention: &str, bytes: usize) -> String {
+    format!("passing-{}({}B)", convention, bytes)
+}
+
+// === ตารางเทียบวิธีส่ง parameter ===
+// CRITICAL: Per struct size: bytes/level and eval ns/level for every convention side by side
+pub fn render_table(format: Format, results: &[&ScenarioResult]) -> String {
+    let find = |name: String| results.iter().copied().find(|r| r.name == name);
+    let rows: Vec<Vec<String>> = SIZES
+        .iter()
+        .filter(|&&bytes| {
+            CONVENTIONS
+                .iter()
+                .any(|c| find(scenario_name(c, bytes)).is_some())
+        })
+        .map(|&bytes| {
+            let mut row = vec![bytes.to_string()];
+            for convention in CONVENTIONS {
+                row.push(output::per_level_cell(find(scenario_name(
+                    convention, bytes,
+                ))));
+            }
+            for convention in CONVENTIONS {
+                let eval = find(scenario_name(convention, bytes))
+                    .and_then(|r| r.timing.as_ref())
+                    .map_or("-".to_string(), |t| format!("{:.1}", t.eval_ns_per_level));
+                row.push(eval);
+            }
+            row
+        })
+        .collect();
+    let depth = results.first().map_or(0, |r| r.depth);
+    output::render_grid(
+        format,
+        &format!(
+            "Parameter passing: bytes/level and eval ns/level (depth {})",
+            depth
+        ),
+        &[
+            "Struct (bytes)",
+            "value B/lvl",
+            "&T B/lvl",
+            "Box<T> B/lvl",
+            "&mut T B/lvl",
+            "value ns/lvl",
+            "&T ns/lvl",
+            "Box<T> ns/lvl",
+            "&mut T ns/lvl",
+        ],
+        &rows,
+    )
+}
@@ -0,0 +1,152 @@
+use crate::output::{self, Format};
+use crate::scenarios::{measure, Measurement, Scenario, ScenarioResult};
+use crate::BoxedFact;
+use stacker::remaining_stack;
+use std::hint::black_box;
+
+// IMPORTANT: u8/u64/u128 only show two data points. Sweeping a const-generic payload
+// from 0 to 512 bytes shows where the frame starts to grow with the data passed by value,
+// and whether moving the same payload into BoxedFact nodes keeps the frame flat.
+
+pub trait Payload: Copy + 'static {
+    fn filled(seed: u8) -> Self;
+}
+
+impl<const N: usize> Payload for [u8; N] {
+    fn filled(seed: u8) -> Self {
+        [seed; N]
+    }
+}
+
+impl<const N: usize> Payload for [u64; N] {
+    fn filled(seed: u8) -> Self {
+        [seed as u64; N]
+    }
+}
+
+// === payload ผ่าน parameter (pure stack) ===
+pub fn payload_tracked<T: Payload>(n: u64, payload: T, stack_info: &mut Vec<usize>) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n > 0 {
+        // black_box forces a real copy of the payload into every call
+        payload_tracked(n - 1, black_box(payload), stack_info);
+    }
+}
+
+// === payload ใน BoxedFact node (heap) ===
+pub fn make_boxed_payload<T: Payload>(n: u64) -> BoxedFact<T> {
+    // IMPORTANT: Iterative creation, same as make_boxed_fact_u64
+    let mut current = BoxedFact::Done(T::filled(0));
+    for i in 1..=n {
+        current = BoxedFact::Next(T::filled(i as u8), Box::new(current));
+    }
+    current
+}
+
+// CRITICAL: Not eval_boxed_fact_tracked - that call sits in tail position and release
+// turns it into a loop. Reading the node's payload after the call returns keeps every frame
+// alive, the same trick unwind.rs uses, so the boxed column is a real recursion too.
+pub fn eval_boxed_payload_tracked<T: Payload>(f: &BoxedFact<T>, stack_info: &mut Vec<usize>) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    match f {
+        BoxedFact::Next(payload, next) => {
+            eval_boxed_payload_tracked(next, stack_info);
+            black_box(payload);
+        }
+        BoxedFact::Done(payload) => {
+            black_box(payload);
+        }
+    }
+}
+
+pub fn run_pure<T: Payload>(n: u64) -> Measurement {
+    measure(
+        n,
+        || T::filled(1),
+        |payload, stack_info| payload_tracked(n, *payload, stack_info),
+    )
+}
+
+pub fn run_boxed<T: Payload>(n: u64) -> Measurement {
+    measure(
+        n,
+        || make_boxed_payload::<T>(n),
+        |fact, stack_info| eval_boxed_payload_tracked(fact, stack_info),
+    )
+}
+
+macro_rules! payload_sweep {
+    ($($bytes:literal),*) => {
+        pub const SIZES: &[usize] = &[$($bytes),*];
+
+        pub const SCENARIOS: &[Scenario] = &[$(
+            Scenario {
+                name: concat!("payload-pure(", $bytes, "B align1)"),
+                run: run_pure::<[u8; $bytes]>,
+                iterative: false,
+                requires_unwind: false,
+            },
+            Scenario {
+                name: concat!("payload-boxed(", $bytes, "B align1)"),
+                run: run_boxed::<[u8; $bytes]>,
+                iterative: false,
+                requires_unwind: false,
+            },
+            Scenario {
+                name: concat!("payload-pure(", $bytes, "B align8)"),
+                run: run_pure::<[u64; $bytes / 8]>,
+                iterative: false,
+                requires_unwind: false,
+            },
+            Scenario {
+                name: concat!("payload-boxed(", $bytes, "B align8)"),
+                run: run_boxed::<[u64; $bytes / 8]>,
+                iterative: false,
+                requires_unwind: false,
+            },
+        )*];
+    };
+}
+
+payload_sweep!(0, 8, 16, 24, 32, 48, 64, 96, 128, 192, 256, 320, 384, 448, 512);
+
+const COLUMNS: &[(&str, &str)] = &[
+    ("pure", "align1"),
+    ("boxed", "align1"),
+    ("pure", "align8"),
+    ("boxed", "align8"),
+];
+
+// === เส้นกราฟ per-level bytes เทียบขนาด payload ===
+// CRITICAL: One row per payload size, one column per variant - the curve itself
+pub fn render_curve(format: Format, results: &[&ScenarioResult]) -> String {
+    let find = |name: String| results.iter().copied().find(|r| r.name == name);
+    let rows: Vec<Vec<String>> = SIZES
+        .iter()
+        .map(|bytes| {
+            std::iter::once(bytes.to_string())
+                .chain(COLUMNS.iter().map(|(shape, align)| {
+                    output::per_level_cell(find(format!("payload-{}({}B {})", shape, bytes, align)))
+                }))
+                .collect()
+        })
+        .collect();
+    let columns: Vec<String> = COLUMNS
+        .iter()
+        .map(|(shape, align)| format!("{} {}", shape, align))
+        .collect();
+    let header: Vec<&str> = std::iter::once("Payload (bytes)")
+        .chain(columns.iter().map(String::as_str))
+        .collect();
+    let depth = results.first().map_or(0, |r| r.depth);
+    output::render_grid(
+        format,
+        &format!("Bytes per level vs payload size (depth {})", depth),
+        &header,
+        &rows,
+    )
+}
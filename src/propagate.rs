@@ -0,0 +1,183 @@
+use crate::scenarios::{measure, Measurement, Scenario};
+use crate::sret::LargeError;
+use crate::{make_boxed_fact_u64, BoxedFact};
+use stacker::remaining_stack;
+use std::hint::black_box;
+
+// IMPORTANT: Mirrors simple_factorial_tracked_u64 and eval_boxed_fact_tracked, but every
+// level returns Result and forwards the child's error with `?`. The frame is laid out for
+// BOTH outcomes, so the error type sizes the return slot even when nothing fails - the
+// "err" variants fail at the leaf and show what propagating the error all the way up costs.
+// CRITICAL: The usual samples are all taken on the way down, before anything is returned,
+// so on their own they cannot tell the two paths apart. Every level also samples right
+// after its child returns - while the Ok or the Err is being forwarded - and the lowest of
+// those is appended as one extra sample once the result reaches the top. Where ok and err
+// rows still agree, forwarding the error really needed no extra stack - only eval time.
+
+const LARGE_ERROR_BYTES: usize = 256;
+
+pub trait ErrorValue: Sized {
+    fn at(level: u64) -> Self;
+    fn level(&self) -> u64;
+}
+
+#[derive(Clone, Copy)]
+pub struct SmallError(pub u64);
+
+impl ErrorValue for SmallError {
+    fn at(level: u64) -> Self {
+        SmallError(level)
+    }
+
+    fn level(&self) -> u64 {
+        self.0
+    }
+}
+
+impl<const N: usize> ErrorValue for LargeError<N> {
+    fn at(level: u64) -> Self {
+        LargeError([level as u8; N])
+    }
+
+    fn level(&self) -> u64 {
+        self.0.first().copied().unwrap_or_default() as u64
+    }
+}
+
+// Low-water mark of the return phase (leaf back up to the top)
+fn sample_return(low: &mut usize) {
+    if let Some(rem) = remaining_stack() {
+        *low = (*low).min(rem);
+    }
+}
+
+// === fn ธรรมดา + Result ===
+pub fn simple_factorial_result_u64<E: ErrorValue>(
+    n: u64,
+    fail_at_leaf: bool,
+    stack_info: &mut Vec<usize>,
+    low: &mut usize,
+) -> Result<u64, E> {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n == 0 {
+        let leaf = if fail_at_leaf { Err(E::at(n)) } else { Ok(1) };
+        sample_return(low);
+        return black_box(leaf);
+    }
+    let below = simple_factorial_result_u64::<E>(n - 1, fail_at_leaf, stack_info, low);
+    sample_return(low);
+    Ok(below?.wrapping_mul(n))
+}
+
+// === Box recursion + Result ===
+pub fn eval_boxed_fact_result<T, E: ErrorValue>(
+    f: &BoxedFact<T>,
+    fail_at_leaf: bool,
+    stack_info: &mut Vec<usize>,
+    low: &mut usize,
+) -> Result<u64, E> {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    let result = match f {
+        BoxedFact::Next(_, next) => {
+            let below = eval_boxed_fact_result::<T, E>(next, fail_at_leaf, stack_info, low);
+            sample_return(low);
+            return Ok(below? + 1);
+        }
+        BoxedFact::Done(_) if fail_at_leaf => Err(E::at(0)),
+        BoxedFact::Done(_) => Ok(0),
+    };
+    sample_return(low);
+    black_box(result)
+}
+
+// One extra sample at the end, so used/per-level cover the return phase as well
+fn push_return_sample(stack_info: &mut Vec<usize>, low: usize) {
+    if low != usize::MAX {
+        stack_info.push(low);
+    }
+}
+
+fn run_simple<E: ErrorValue, const FAIL: bool>(n: u64) -> Measurement {
+    measure(
+        n,
+        || (),
+        |_, stack_info| {
+            let mut low = usize::MAX;
+            if let Err(e) = simple_factorial_result_u64::<E>(n, FAIL, stack_info, &mut low) {
+                black_box(e.level());
+            }
+            push_return_sample(stack_info, low);
+        },
+    )
+}
+
+fn run_boxed<E: ErrorValue, const FAIL: bool>(n: u64) -> Measurement {
+    measure(
+        n,
+        || make_boxed_fact_u64(n),
+        |fact, stack_info| {
+            let mut low = usize::MAX;
+            if let Err(e) = eval_boxed_fact_result::<u64, E>(fact, FAIL, stack_info, &mut low) {
+                black_box(e.level());
+            }
+            push_return_sample(stack_info, low);
+        },
+    )
+}
+
+type Large = LargeError<LARGE_ERROR_BYTES>;
+
+pub const SCENARIOS: &[Scenario] = &[
+    Scenario {
+        name: "result-simple(small ok)",
+        run: run_simple::<SmallError, false>,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "result-simple(small err)",
+        run: run_simple::<SmallError, true>,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "result-simple(large ok)",
+        run: run_simple::<Large, false>,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "result-simple(large err)",
+        run: run_simple::<Large, true>,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "result-boxed(small ok)",
+        run: run_boxed::<SmallError, false>,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "result-boxed(small err)",
+        run: run_boxed::<SmallError, true>,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "result-boxed(large ok)",
+        run: run_boxed::<Large, false>,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "result-boxed(large err)",
+        run: run_boxed::<Large, true>,
+        iterative: false,
+        requires_unwind: false,
+    },
+];
@@ -0,0 +1,53 @@
+use crate::scenarios::Measurement;
+use crate::workers::WorkerContext;
+use rayon::prelude::*;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+
+pub const WORKER_THREADS: usize = 4;
+pub const CONCURRENT_RUNS: usize = 2 * WORKER_THREADS;
+
+// IMPORTANT: ThreadPoolBuilder::stack_size sets every pool thread. install runs one
+// recursion on a pool thread; the par_iter context starts CONCURRENT_RUNS copies of the same
+// scenario at once, more than there are threads, so each recursion sits on top of rayon's
+// join frames and a thread that finishes early steals the next copy - the shape of a deep
+// par_iter closure.
+// CRITICAL: Both install and par_iter re-raise a panic from the pool on the calling thread.
+// It is caught here and reported as a failed run, like a tokio JoinError.
+pub fn contexts(stack_size: usize) -> Result<Vec<WorkerContext<'static>>, String> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(WORKER_THREADS)
+        .stack_size(stack_size)
+        .thread_name(|i| format!("rayon-worker-{}", i))
+        .build()
+        .map_err(|e| format!("cannot start the rayon pool: {}", e))?;
+    let pool = Arc::new(pool);
+    let concurrent = Arc::clone(&pool);
+    Ok(vec![
+        WorkerContext {
+            label: "rayon install",
+            runner: Box::new(move |scenario, depth| {
+                let run = scenario.run;
+                panic::catch_unwind(AssertUnwindSafe(|| pool.install(move || run(depth)))).ok()
+            }),
+        },
+        WorkerContext {
+            label: "rayon par_iter",
+            runner: Box::new(move |scenario, depth| {
+                let run = scenario.run;
+                let runs: Vec<Measurement> = panic::catch_unwind(AssertUnwindSafe(|| {
+                    concurrent.install(move || {
+                        (0..CONCURRENT_RUNS)
+                            .into_par_iter()
+                            .map(|_| run(depth))
+                            .collect()
+                    })
+                }))
+                .ok()?;
+                // CRITICAL: Report the copy that started with the least stack left
+                runs.into_iter()
+                    .min_by_key(|m| m.stack_info.first().copied().unwrap_or(usize::MAX))
+            }),
+        },
+    ])
+}
@@ -0,0 +1,400 @@
+use crate::alignment;
+use crate::async_rec;
+use crate::closure;
+use crate::dyn_node;
+use crate::factorial;
+use crate::fair_string;
+use crate::fold;
+use crate::interp;
+use crate::iter_chain;
+use crate::metadata::Metadata;
+use crate::mutual;
+use crate::parser;
+use crate::passing;
+use crate::payload;
+use crate::propagate;
+use crate::sret;
+use crate::stats::RunStats;
+use crate::tree;
+use crate::unwind;
+use crate::{
+    analyze_stack, eval_boxed_fact_tracked, eval_boxed_string_tracked, make_boxed_fact_u128,
+    make_boxed_fact_u64, make_boxed_fact_u8, make_boxed_string, simple_factorial_tracked_u128,
+    simple_factorial_tracked_u64, simple_factorial_tracked_u8, simple_string_tracked, StackReport,
+};
+use std::hint::black_box;
+use std::time::Instant;
+
+// === ทะเบียน scenario ===
+// IMPORTANT: Every scenario runs one tracked recursion at the requested depth and returns
+// the remaining_stack() samples plus phase timings. The runner turns these into a StackReport.
+pub struct Scenario {
+    pub name: &'static str,
+    pub run: fn(u64) -> Measurement,
+    // Walks with a loop on purpose (fold, flat iterator, heap-stack parser). Its flat stack
+    // is the design, not tail-call elimination, and is reported as such.
+    pub iterative: bool,
+    // Catches a panic on purpose, so a panic=abort build skips it instead of aborting
+    pub requires_unwind: bool,
+}
+
+pub struct Measurement {
+    pub stack_info: Vec<usize>,
+    pub build_ns: f64, // make_boxed_* (or argument setup for pure recursion)
+    pub eval_ns: f64,  // The tracked recursion itself
+    pub drop_ns: f64,  // Dropping what build created - one node at a time for Box chains
+}
+
+// Median phase cost of all measured runs, divided by the number of levels
+pub struct PhaseTiming {
+    pub build_ns_per_level: f64,
+    pub eval_ns_per_level: f64,
+    pub drop_ns_per_level: f64,
+}
+
+pub struct ScenarioResult {
+    pub profile: Option<String>, // Build profile label (matrix mode only)
+    pub name: String,
+    pub depth: u64,
+    pub iterative: bool,             // Copied from Scenario::iterative
+    pub report: Option<StackReport>, // None when the run panicked, was skipped or took < 2 samples
+    pub skipped: Option<String>,     // Why the scenario was not run at this depth
+    pub timing: Option<PhaseTiming>,
+    pub stats: Option<RunStats>, // Only with --repeat > 1
+    pub metadata: Metadata,
+}
+
+pub struct RunConfig {
+    pub stack_size: usize,
+    pub repeat: usize,
+    pub warmup: usize,
+    pub spread_threshold: f64, // Relative disagreement between runs that gets flagged
+}
+
+pub const SCENARIOS: &[Scenario] = &[
+    Scenario {
+        name: "simple(u8)",
+        run: run_simple_u8,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "simple(u64)",
+        run: run_simple_u64,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "simple(u128)",
+        run: run_simple_u128,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "boxed(u8)",
+        run: run_boxed_u8,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "boxed(u64)",
+        run: run_boxed_u64,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "boxed(u128)",
+        run: run_boxed_u128,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "string(pure)",
+        run: run_string_pure,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "string(boxed)",
+        run: run_string_boxed,
+        iterative: false,
+        requires_unwind: false,
+    },
+];
+
+// IMPORTANT: Each scenario family lives in its own module and lists its scenarios here
+pub fn registry() -> Vec<&'static Scenario> {
+    SCENARIOS
+        .iter()
+        .chain(fair_string::SCENARIOS)
+        .chain(payload::SCENARIOS)
+        .chain(alignment::SCENARIOS)
+        .chain(passing::SCENARIOS)
+        .chain(sret::SCENARIOS)
+        .chain(propagate::SCENARIOS)
+        .chain(unwind::SCENARIOS)
+        .chain(mutual::SCENARIOS)
+        .chain(tree::SCENARIOS)
+        .chain(parser::SCENARIOS)
+        .chain(interp::SCENARIOS)
+        .chain(dyn_node::SCENARIOS)
+        .chain(closure::SCENARIOS)
+        .chain(closure::CAPTURED)
+        .chain(async_rec::SCENARIOS)
+        .chain(iter_chain::SCENARIOS)
+        .chain(fold::SCENARIOS)
+        .chain(factorial::SCENARIOS)
+        .collect()
+}
+
+// IMPORTANT: u8 depth is clamped - `n as u8` would silently wrap (60_000 as u8 = 96)
+fn depth_u8(n: u64) -> u8 {
+    n.min(u8::MAX as u64) as u8
+}
+
+// CRITICAL: Times build, eval and drop separately. black_box keeps the optimizer from
+// deleting construction or evaluation whose result is never looked at.
+pub fn measure<T>(
+    n: u64,
+    build: impl FnOnce() -> T,
+    eval: impl FnOnce(&mut T, &mut Vec<usize>),
+) -> Measurement {
+    let start = Instant::now();
+    let mut value = black_box(build());
+    let build_ns = start.elapsed().as_nanos() as f64;
+
+    // Reserve up front so Vec growth is not timed as part of the recursion
+    let mut stack_info = Vec::with_capacity(n as usize + 2);
+    let start = Instant::now();
+    eval(black_box(&mut value), &mut stack_info);
+    let eval_ns = start.elapsed().as_nanos() as f64;
+
+    let start = Instant::now();
+    drop(black_box(value));
+    let drop_ns = start.elapsed().as_nanos() as f64;
+
+    Measurement {
+        stack_info,
+        build_ns,
+        eval_ns,
+        drop_ns,
+    }
+}
+
+fn run_simple_u8(n: u64) -> Measurement {
+    measure(
+        n,
+        || depth_u8(n),
+        |n, stack_info| simple_factorial_tracked_u8(*n, stack_info),
+    )
+}
+
+fn run_simple_u64(n: u64) -> Measurement {
+    measure(
+        n,
+        || n,
+        |n, stack_info| simple_factorial_tracked_u64(*n, stack_info),
+    )
+}
+
+fn run_simple_u128(n: u64) -> Measurement {
+    measure(
+        n,
+        || n as u128,
+        |n, stack_info| simple_factorial_tracked_u128(*n, stack_info),
+    )
+}
+
+fn run_boxed_u8(n: u64) -> Measurement {
+    measure(
+        n,
+        || make_boxed_fact_u8(depth_u8(n)),
+        |fact, stack_info| eval_boxed_fact_tracked(fact, stack_info),
+    )
+}
+
+fn run_boxed_u64(n: u64) -> Measurement {
+    measure(
+        n,
+        || make_boxed_fact_u64(n),
+        |fact, stack_info| eval_boxed_fact_tracked(fact, stack_info),
+    )
+}
+
+fn run_boxed_u128(n: u64) -> Measurement {
+    measure(
+        n,
+        || make_boxed_fact_u128(n as u128),
+        |fact, stack_info| eval_boxed_fact_tracked(fact, stack_info),
+    )
+}
+
+fn run_string_pure(n: u64) -> Measurement {
+    measure(
+        n,
+        || String::with_capacity((n as usize) * 4),
+        |s, stack_info| simple_string_tracked(n, stack_info, s),
+    )
+}
+
+fn run_string_boxed(n: u64) -> Measurement {
+    measure(
+        n,
+        || {
+            (
+                make_boxed_string(n),
+                String::with_capacity((n as usize) * 4),
+            )
+        },
+        |(tree, out), stack_info| eval_boxed_string_tracked(tree, stack_info, out),
+    )
+}
+
+// Runs one scenario at one depth somewhere and hands back its measurement
+pub type Runner<'a> = &'a dyn Fn(&Scenario, u64) -> Option<Measurement>;
+
+// CRITICAL: Each scenario runs on its own thread so every measurement starts from the
+// same, known stack size instead of whatever main() has already used
+pub fn run_once(scenario: &Scenario, depth: u64, stack_size: usize) -> Option<Measurement> {
+    let run = scenario.run;
+    std::thread::Builder::new()
+        .name(scenario.name.to_string())
+        .stack_size(stack_size)
+        .spawn(move || run(depth))
+        .ok()
+        .and_then(|handle| handle.join().ok())
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(f64::total_cmp);
+    values[values.len() / 2]
+}
+
+// IMPORTANT: A real stack overflow aborts the whole process, so before the full run two
+// shallow probes estimate the stack the requested depth needs. Scenarios that would use
+// more than STACK_SAFETY of the measuring thread's stack are skipped instead of crashing.
+const PROBE_DEPTH: u64 = 64;
+const STACK_SAFETY: f64 = 0.75;
+
+fn probe(scenario: &Scenario, depth: u64, runner: Runner) -> Option<(StackReport, usize)> {
+    let m = runner(scenario, depth)?;
+    Some((analyze_stack(&m.stack_info)?, *m.stack_info.first()?))
+}
+
+fn check_fits(scenario: &Scenario, depth: u64, runner: Runner) -> Result<(), String> {
+    if depth <= 2 * PROBE_DEPTH {
+        return Ok(());
+    }
+    // Let the real run report a failure
+    let Some((report, available)) = probe(scenario, PROBE_DEPTH, runner) else {
+        return Ok(());
+    };
+    let Some((doubled, _)) = probe(scenario, 2 * PROBE_DEPTH, runner) else {
+        return Ok(());
+    };
+    // KEY: Levels usually equal n, but a balanced tree only gets one level deeper when n
+    // doubles. Fit levels = a * n^k through both probes instead of assuming k = 1.
+    let growth = ((2 * PROBE_DEPTH + 1) as f64 / (PROBE_DEPTH + 1) as f64).ln();
+    let k = ((doubled.levels as f64 / report.levels as f64).ln() / growth).clamp(0.0, 1.0);
+    let levels = report.levels as f64 * ((depth + 1) as f64 / (PROBE_DEPTH + 1) as f64).powf(k);
+    let needed = report.per_level * levels;
+    if needed > available as f64 * STACK_SAFETY {
+        return Err(format!(
+            "needs ~{} of {} bytes of stack ({:.2} per level)",
+            needed as usize, available, report.per_level
+        ));
+    }
+    Ok(())
+}
+
+pub fn run_scenario(
+    scenario: &Scenario,
+    depth: u64,
+    config: &RunConfig,
+    metadata: &Metadata,
+) -> ScenarioResult {
+    run_scenario_on(scenario, depth, config, metadata, &|scenario, depth| {
+        run_once(scenario, depth, config.stack_size)
+    })
+}
+
+// IMPORTANT: Same probe, warmup, repeat and statistics as run_scenario, but every run
+// (probes included) goes through `runner` - e.g. onto a tokio or rayon worker thread
+pub fn run_scenario_on(
+    scenario: &Scenario,
+    depth: u64,
+    config: &RunConfig,
+    metadata: &Metadata,
+    runner: Runner,
+) -> ScenarioResult {
+    let skip = if scenario.requires_unwind && cfg!(panic = "abort") {
+        Err(
+            "panic=abort build: a panic aborts the process, catch_unwind cannot catch it"
+                .to_string(),
+        )
+    } else {
+        check_fits(scenario, depth, runner)
+    };
+    if let Err(reason) = skip {
+        return ScenarioResult {
+            profile: None,
+            name: scenario.name.to_string(),
+            depth,
+            iterative: scenario.iterative,
+            report: None,
+            skipped: Some(reason),
+            timing: None,
+            stats: None,
+            metadata: metadata.clone(),
+        };
+    }
+    for _ in 0..config.warmup {
+        runner(scenario, depth);
+    }
+    let runs: Vec<_> = (0..config.repeat.max(1))
+        .map(|_| runner(scenario, depth))
+        .collect();
+
+    // IMPORTANT: A failed run fails the scenario - partial statistics would hide it
+    let measured: Option<Vec<_>> = runs
+        .into_iter()
+        .map(|run| run.and_then(|m| analyze_stack(&m.stack_info).map(|report| (report, m))))
+        .collect();
+    let (report, timing, stats) = match measured {
+        Some(mut measured) if !measured.is_empty() => {
+            let per_level: Vec<f64> = measured.iter().map(|(r, _)| r.per_level).collect();
+            let time_ns: Vec<f64> = measured
+                .iter()
+                .map(|(_, m)| m.build_ns + m.eval_ns + m.drop_ns)
+                .collect();
+            let stats = (measured.len() > 1)
+                .then(|| RunStats::from_runs(&per_level, &time_ns, config.spread_threshold))
+                .flatten();
+            let levels = measured[0].0.levels as f64;
+            let phase = |f: fn(&Measurement) -> f64| {
+                median(measured.iter().map(|(_, m)| f(m)).collect()) / levels
+            };
+            let timing = PhaseTiming {
+                build_ns_per_level: phase(|m| m.build_ns),
+                eval_ns_per_level: phase(|m| m.eval_ns),
+                drop_ns_per_level: phase(|m| m.drop_ns),
+            };
+            // Report the median run so one odd run cannot become the headline number
+            measured.sort_by(|a, b| a.0.per_level.total_cmp(&b.0.per_level));
+            let mid = measured.len() / 2;
+            (Some(measured.swap_remove(mid).0), Some(timing), stats)
+        }
+        _ => (None, None, None),
+    };
+    ScenarioResult {
+        profile: None,
+        name: scenario.name.to_string(),
+        depth,
+        iterative: scenario.iterative,
+        report,
+        skipped: None,
+        timing,
+        stats,
+        metadata: metadata.clone(),
+    }
+}
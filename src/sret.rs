@@ -0,0 +1,182 @@
+use crate::output::{self, Format};
+use crate::payload::Payload;
+use crate::scenarios::{measure, Measurement, Scenario, ScenarioResult};
+use stacker::remaining_stack;
+use std::hint::black_box;
+use std::mem::size_of;
+
+// IMPORTANT: Every other tracked function returns (). Real parsers return big values from
+// every recursive call, and anything larger than two registers is returned through a
+// hidden pointer to a return slot (sret) that the CALLER reserves in its own frame.
+// These scenarios return payloads of growing size to show how that slot inflates frames.
+
+#[derive(Clone, Copy)]
+pub struct LargeError<const N: usize>(pub [u8; N]);
+
+// === คืนค่าขนาดใหญ่ตรงๆ ===
+pub fn return_value_tracked<T: Payload>(n: u64, stack_info: &mut Vec<usize>) -> T {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n == 0 {
+        return T::filled(0);
+    }
+    // IMPORTANT: Inspecting the child's value keeps it in a slot of THIS frame - returning
+    // the call directly would just forward the caller's return pointer (no copy at all)
+    let child = return_value_tracked::<T>(n - 1, stack_info);
+    black_box(&child);
+    child
+}
+
+// === คืน Result<u64, LargeError> (happy path - error type sets the size) ===
+pub fn return_result_tracked<const N: usize>(
+    n: u64,
+    stack_info: &mut Vec<usize>,
+) -> Result<u64, LargeError<N>> {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n == 0 {
+        return Ok(0);
+    }
+    let child = return_result_tracked::<N>(n - 1, stack_info);
+    black_box(&child);
+    child
+}
+
+// === คืน Option<[u64; N]> ===
+pub fn return_option_tracked<const N: usize>(
+    n: u64,
+    stack_info: &mut Vec<usize>,
+) -> Option<[u64; N]> {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n == 0 {
+        return Some([n; N]);
+    }
+    let child = return_option_tracked::<N>(n - 1, stack_info);
+    black_box(&child);
+    child
+}
+
+fn run_value<T: Payload>(n: u64) -> Measurement {
+    measure(
+        n,
+        || (),
+        |_, stack_info| {
+            black_box(return_value_tracked::<T>(n, stack_info));
+        },
+    )
+}
+
+fn run_result<const N: usize>(n: u64) -> Measurement {
+    measure(
+        n,
+        || (),
+        |_, stack_info| {
+            let _ = black_box(return_result_tracked::<N>(n, stack_info));
+        },
+    )
+}
+
+fn run_option<const N: usize>(n: u64) -> Measurement {
+    measure(
+        n,
+        || (),
+        |_, stack_info| {
+            black_box(return_option_tracked::<N>(n, stack_info));
+        },
+    )
+}
+
+pub const KINDS: &[&str] = &["value", "result", "option"];
+
+pub struct ReturnSizes {
+    pub bytes: usize,
+    pub value: usize,  // size_of::<[u8; bytes]>()
+    pub result: usize, // size_of::<Result<u64, LargeError<bytes>>>()
+    pub option: usize, // size_of::<Option<[u64; bytes / 8]>>()
+}
+
+macro_rules! sret_sizes {
+    ($($bytes:literal),*) => {
+        pub const SIZES: &[usize] = &[$($bytes),*];
+
+        pub const TYPES: &[ReturnSizes] = &[$(
+            ReturnSizes {
+                bytes: $bytes,
+                value: size_of::<[u8; $bytes]>(),
+                result: size_of::<Result<u64, LargeError<$bytes>>>(),
+                option: size_of::<Option<[u64; $bytes / 8]>>(),
+            },
+        )*];
+
+        pub const SCENARIOS: &[Scenario] = &[$(
+            Scenario {
+                name: concat!("sret-value(", $bytes, "B)"),
+                run: run_value::<[u8; $bytes]>,
+                iterative: false,
+                requires_unwind: false,
+            },
+            Scenario {
+                name: concat!("sret-result(", $bytes, "B)"),
+                run: run_result::<$bytes>,
+                iterative: false,
+                requires_unwind: false,
+            },
+            Scenario {
+                name: concat!("sret-option(", $bytes, "B)"),
+                run: run_option::<{ $bytes / 8 }>,
+                iterative: false,
+                requires_unwind: false,
+            },
+        )*];
+    };
+}
+
+sret_sizes!(8, 16, 32, 64, 128, 256, 512, 1024);
+
+pub fn scenario_name(kind: &str, bytes: usize) -> String {
+    format!("sret-{}({}B)", kind, bytes)
+}
+
+// === ตาราง return slot ===
+// CRITICAL: size_of the returned type next to bytes/level - growth tracks the return slot
+pub fn render_table(format: Format, results: &[&ScenarioResult]) -> String {
+    let find = |name: String| results.iter().copied().find(|r| r.name == name);
+    let rows: Vec<Vec<String>> = TYPES
+        .iter()
+        .filter(|s| {
+            KINDS
+                .iter()
+                .any(|k| find(scenario_name(k, s.bytes)).is_some())
+        })
+        .map(|s| {
+            vec![
+                s.bytes.to_string(),
+                s.value.to_string(),
+                output::per_level_cell(find(scenario_name("value", s.bytes))),
+                s.result.to_string(),
+                output::per_level_cell(find(scenario_name("result", s.bytes))),
+                s.option.to_string(),
+                output::per_level_cell(find(scenario_name("option", s.bytes))),
+            ]
+        })
+        .collect();
+    let depth = results.first().map_or(0, |r| r.depth);
+    output::render_grid(
+        format,
+        &format!("Return value size vs bytes/level (depth {})", depth),
+        &[
+            "Payload (bytes)",
+            "[u8; N] size",
+            "value B/lvl",
+            "Result size",
+            "Result B/lvl",
+            "Option size",
+            "Option B/lvl",
+        ],
+        &rows,
+    )
+}
@@ -0,0 +1,143 @@
+// === สถิติจากการรันซ้ำ ===
+// IMPORTANT: A single odd run must not end up in the docs. Samples further than
+// OUTLIER_MADS scaled median absolute deviations from the median are counted as outliers
+// and left out of mean/stddev; min/max still show the raw extremes.
+const OUTLIER_MADS: f64 = 3.0;
+const MAD_TO_STDDEV: f64 = 1.4826;
+
+#[derive(Clone, Default)]
+pub struct Stats {
+    pub mean: f64,
+    pub median: f64,
+    pub stddev: f64,
+    pub min: f64,
+    pub max: f64,
+    pub outliers: usize,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[f64]) -> Option<Stats> {
+        if samples.is_empty() {
+            return None;
+        }
+        let median = median(samples);
+        let deviations: Vec<f64> = samples.iter().map(|x| (x - median).abs()).collect();
+        let limit = OUTLIER_MADS * MAD_TO_STDDEV * self::median(&deviations);
+        let kept: Vec<f64> = samples
+            .iter()
+            .copied()
+            .filter(|x| (x - median).abs() <= limit)
+            .collect();
+
+        let mean = kept.iter().sum::<f64>() / kept.len() as f64;
+        let variance = kept.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / kept.len() as f64;
+        Some(Stats {
+            mean,
+            median,
+            stddev: variance.sqrt(),
+            min: samples.iter().copied().fold(f64::INFINITY, f64::min),
+            max: samples.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            outliers: samples.len() - kept.len(),
+        })
+    }
+
+    // CRITICAL: Relative spread of the raw samples - (max - min) / median
+    pub fn spread(&self) -> f64 {
+        if self.median == 0.0 {
+            if self.max == self.min {
+                0.0
+            } else {
+                f64::INFINITY
+            }
+        } else {
+            (self.max - self.min) / self.median
+        }
+    }
+}
+
+// The median sample is never an outlier, so `kept` above is never empty
+fn median(samples: &[f64]) -> f64 {
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+pub struct RunStats {
+    pub runs: usize,
+    pub per_level: Stats, // bytes per level
+    pub time_ns: Stats,   // wall-clock time of the whole run
+    pub disagree: bool,   // runs differ by more than the threshold
+}
+
+impl RunStats {
+    // IMPORTANT: Stack bytes should be identical between runs, so any raw spread beyond the
+    // threshold is suspicious. Timing is always noisy - only its relative stddev is checked.
+    pub fn from_runs(per_level: &[f64], time_ns: &[f64], threshold: f64) -> Option<RunStats> {
+        let runs = per_level.len();
+        let per_level = Stats::from_samples(per_level)?;
+        let time_ns = Stats::from_samples(time_ns)?;
+        let time_cv = if time_ns.mean > 0.0 {
+            time_ns.stddev / time_ns.mean
+        } else {
+            0.0
+        };
+        Some(RunStats {
+            runs,
+            disagree: per_level.spread() > threshold || time_cv > threshold,
+            per_level,
+            time_ns,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_samples_have_no_spread() {
+        let stats = Stats::from_samples(&[48.0; 5]).unwrap();
+        assert_eq!((stats.mean, stats.median, stats.stddev), (48.0, 48.0, 0.0));
+        assert_eq!(stats.outliers, 0);
+        assert_eq!(stats.spread(), 0.0);
+    }
+
+    #[test]
+    fn far_sample_is_dropped_from_mean_but_kept_in_max() {
+        let stats = Stats::from_samples(&[10.0, 11.0, 9.0, 10.0, 10.0, 1000.0]).unwrap();
+        assert_eq!(stats.outliers, 1);
+        assert_eq!(stats.median, 10.0);
+        assert_eq!(stats.mean, 10.0);
+        assert_eq!(stats.max, 1000.0);
+        assert_eq!(stats.min, 9.0);
+    }
+
+    #[test]
+    fn samples_within_three_mads_are_kept() {
+        // median 10, MAD 1 -> everything within 3 * 1.4826 of 10 stays
+        let stats = Stats::from_samples(&[8.0, 9.0, 10.0, 11.0, 14.0]).unwrap();
+        assert_eq!(stats.outliers, 0);
+        assert_eq!(stats.mean, 10.4);
+    }
+
+    #[test]
+    fn even_count_median_is_the_midpoint() {
+        assert_eq!(median(&[4.0, 1.0, 3.0, 2.0]), 2.5);
+        assert!(Stats::from_samples(&[]).is_none());
+    }
+
+    #[test]
+    fn runs_disagree_beyond_the_threshold() {
+        let steady = RunStats::from_runs(&[48.0, 48.0, 48.0], &[100.0, 101.0, 99.0], 0.1);
+        assert!(!steady.unwrap().disagree);
+        let stack = RunStats::from_runs(&[48.0, 48.0, 64.0], &[100.0, 100.0, 100.0], 0.1);
+        assert!(stack.unwrap().disagree);
+        let time = RunStats::from_runs(&[48.0, 48.0], &[100.0, 200.0], 0.1);
+        assert!(time.unwrap().disagree);
+    }
+}
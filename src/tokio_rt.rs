@@ -0,0 +1,36 @@
+use crate::workers::WorkerContext;
+use std::rc::Rc;
+
+// IMPORTANT: One multi-thread runtime whose thread_stack_size applies to both kinds of
+// thread it owns: the async workers (tokio::spawn) and the blocking pool (spawn_blocking).
+// Running a deep recursion on a worker is exactly the mistake worth measuring.
+pub fn contexts(stack_size: usize) -> Result<Vec<WorkerContext<'static>>, String> {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .thread_stack_size(stack_size)
+        .thread_name("tokio-runtime")
+        .build()
+        .map_err(|e| format!("cannot start the tokio runtime: {}", e))?;
+    let runtime = Rc::new(runtime);
+    let blocking = Rc::clone(&runtime);
+    Ok(vec![
+        WorkerContext {
+            label: "tokio worker",
+            runner: Box::new(move |scenario, depth| {
+                let run = scenario.run;
+                runtime
+                    .block_on(runtime.spawn(async move { run(depth) }))
+                    .ok()
+            }),
+        },
+        WorkerContext {
+            label: "tokio spawn_blocking",
+            runner: Box::new(move |scenario, depth| {
+                let run = scenario.run;
+                blocking
+                    .block_on(blocking.spawn_blocking(move || run(depth)))
+                    .ok()
+            }),
+        },
+    ])
+}
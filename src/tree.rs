@@ -0,0 +1,300 @@
+use crate::output::{self, Format};
+use crate::scenarios::{measure, Measurement, PhaseTiming, Scenario, ScenarioResult};
+use stacker::remaining_stack;
+use std::hint::black_box;
+
+// IMPORTANT: BoxedFact and BoxedString are linked lists - depth equals node count. ASTs and
+// DOMs are trees, where depth and node count come apart. For these scenarios the requested
+// depth is the NODE COUNT, and the evaluators push a sample only when they reach a new
+// maximum depth, so `levels` in the report is max depth + 1 and per-level stays per frame.
+
+// === ต้นไม้ binary ===
+pub struct BinaryTree {
+    pub value: u64,
+    pub left: Option<Box<BinaryTree>>,
+    pub right: Option<Box<BinaryTree>>,
+}
+
+// === ต้นไม้ n-ary ===
+pub struct NaryTree {
+    pub value: u64,
+    pub children: Vec<NaryTree>,
+}
+
+// === drop ทีละ node ===
+// CRITICAL: The derived drop recurses once per level - a degenerate tree is as deep as it
+// has nodes, so dropping it overflowed after eval had already fit. Children are moved onto
+// a heap stack before each node is freed, so no node is dropped with children attached.
+impl Drop for BinaryTree {
+    fn drop(&mut self) {
+        let mut pending: Vec<Box<BinaryTree>> = Vec::new();
+        pending.extend(self.left.take());
+        pending.extend(self.right.take());
+        while let Some(mut node) = pending.pop() {
+            pending.extend(node.left.take());
+            pending.extend(node.right.take());
+        }
+    }
+}
+
+impl Drop for NaryTree {
+    fn drop(&mut self) {
+        let mut pending = std::mem::take(&mut self.children);
+        while let Some(mut node) = pending.pop() {
+            pending.append(&mut node.children);
+        }
+    }
+}
+
+// Recursive builder: values lo..hi, the middle one at the root - depth is log2(count)
+pub fn build_balanced_binary(lo: u64, hi: u64) -> Option<Box<BinaryTree>> {
+    if lo >= hi {
+        return None;
+    }
+    let mid = lo + (hi - lo) / 2;
+    Some(Box::new(BinaryTree {
+        value: mid,
+        left: build_balanced_binary(lo, mid),
+        right: build_balanced_binary(mid + 1, hi),
+    }))
+}
+
+pub fn make_degenerate_binary(count: u64) -> Option<Box<BinaryTree>> {
+    // IMPORTANT: Iterative creation - a recursive builder would need as deep a stack as
+    // the evaluator we are measuring. Every node only has a left child.
+    let mut current = None;
+    for value in 0..count {
+        current = Some(Box::new(BinaryTree {
+            value,
+            left: current,
+            right: None,
+        }));
+    }
+    current
+}
+
+// Builder: the count - 1 nodes below the root are split evenly over `fanout` subtrees.
+// IMPORTANT: Iterative, like make_degenerate_binary - with fanout 1 the tree is as deep as
+// it has nodes. `open` holds the nodes whose children are still being built, root first.
+pub fn build_nary(first: u64, count: u64, fanout: u64) -> NaryTree {
+    struct Open {
+        node: NaryTree,
+        below: u64, // Nodes to split over the children
+        next: u64,  // Value of the next child
+        child: u64, // Index of the next child
+    }
+    let open_node = |value: u64, count: u64| {
+        let below = count.saturating_sub(1);
+        Open {
+            node: NaryTree {
+                value,
+                children: Vec::with_capacity(fanout.min(below) as usize),
+            },
+            below,
+            next: value + 1,
+            child: 0,
+        }
+    };
+    let mut open = vec![open_node(first, count)];
+    loop {
+        let top = open
+            .last_mut()
+            .expect("the root stays open until it is returned");
+        if top.child < fanout {
+            let size = top.below / fanout + u64::from(top.child < top.below % fanout);
+            let value = top.next;
+            top.child += 1;
+            top.next += size;
+            if size > 0 {
+                open.push(open_node(value, size));
+            }
+            continue;
+        }
+        let done = open.pop().expect("checked by last_mut above").node;
+        match open.last_mut() {
+            Some(parent) => parent.node.children.push(done),
+            None => return done,
+        }
+    }
+}
+
+// CRITICAL: Depth-first order reaches every depth for the first time when
+// stack_info.len() == depth, so only new maximum depths are recorded
+pub fn eval_binary_tracked(t: &BinaryTree, depth: usize, stack_info: &mut Vec<usize>) -> u64 {
+    if depth == stack_info.len() {
+        if let Some(rem) = remaining_stack() {
+            stack_info.push(rem);
+        }
+    }
+    let left = t
+        .left
+        .as_ref()
+        .map_or(0, |l| eval_binary_tracked(l, depth + 1, stack_info));
+    let right = t
+        .right
+        .as_ref()
+        .map_or(0, |r| eval_binary_tracked(r, depth + 1, stack_info));
+    t.value.wrapping_add(left).wrapping_add(right)
+}
+
+pub fn eval_nary_tracked(t: &NaryTree, depth: usize, stack_info: &mut Vec<usize>) -> u64 {
+    if depth == stack_info.len() {
+        if let Some(rem) = remaining_stack() {
+            stack_info.push(rem);
+        }
+    }
+    t.children
+        .iter()
+        .map(|child| eval_nary_tracked(child, depth + 1, stack_info))
+        .fold(t.value, u64::wrapping_add)
+}
+
+fn run_balanced_binary(n: u64) -> Measurement {
+    measure(
+        n,
+        || build_balanced_binary(0, n.max(1)),
+        |tree, stack_info| {
+            if let Some(tree) = tree {
+                black_box(eval_binary_tracked(tree, 0, stack_info));
+            }
+        },
+    )
+}
+
+fn run_degenerate_binary(n: u64) -> Measurement {
+    measure(
+        n,
+        || make_degenerate_binary(n.max(1)),
+        |tree, stack_info| {
+            if let Some(tree) = tree {
+                black_box(eval_binary_tracked(tree, 0, stack_info));
+            }
+        },
+    )
+}
+
+fn run_nary<const FANOUT: u64>(n: u64) -> Measurement {
+    measure(
+        n,
+        || build_nary(0, n.max(1), FANOUT),
+        |tree, stack_info| {
+            black_box(eval_nary_tracked(tree, 0, stack_info));
+        },
+    )
+}
+
+pub const SCENARIOS: &[Scenario] = &[
+    Scenario {
+        name: "tree-binary(balanced)",
+        run: run_balanced_binary,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "tree-binary(degenerate)",
+        run: run_degenerate_binary,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "tree-nary(4)",
+        run: run_nary::<4>,
+        iterative: false,
+        requires_unwind: false,
+    },
+    Scenario {
+        name: "tree-nary(16)",
+        run: run_nary::<16>,
+        iterative: false,
+        requires_unwind: false,
+    },
+];
+
+// === ตาราง max depth เทียบจำนวน node ===
+// CRITICAL: levels is max depth + 1, so time per node = ns/level * levels / nodes. Build
+// and drop visit every node too, so they get the same conversion.
+pub fn render_table(format: Format, results: &[&ScenarioResult]) -> String {
+    let rows: Vec<Vec<String>> = SCENARIOS
+        .iter()
+        .filter_map(|scenario| {
+            let result = results.iter().copied().find(|r| r.name == scenario.name)?;
+            let nodes = result.depth.max(1);
+            let levels = result.report.as_ref().map(|r| r.levels);
+            let per_node = |phase: fn(&PhaseTiming) -> f64| {
+                levels
+                    .zip(result.timing.as_ref())
+                    .map_or("-".to_string(), |(l, t)| {
+                        format!("{:.1}", phase(t) * l as f64 / nodes as f64)
+                    })
+            };
+            Some(vec![
+                scenario.name.to_string(),
+                nodes.to_string(),
+                levels.map_or("-".to_string(), |l| (l - 1).to_string()),
+                output::per_level_cell(Some(result)),
+                result
+                    .report
+                    .as_ref()
+                    .map_or("-".to_string(), |r| r.used.to_string()),
+                per_node(|t| t.build_ns_per_level),
+                per_node(|t| t.eval_ns_per_level),
+                per_node(|t| t.drop_ns_per_level),
+            ])
+        })
+        .collect();
+    let depth = results.first().map_or(0, |r| r.depth);
+    output::render_grid(
+        format,
+        &format!("Tree shape: max depth vs node count ({} nodes)", depth),
+        &[
+            "Scenario",
+            "Nodes",
+            "Max depth",
+            "B/lvl",
+            "Stack used (bytes)",
+            "Build ns/node",
+            "Eval ns/node",
+            "Drop ns/node",
+        ],
+        &rows,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nary_nodes(tree: &NaryTree) -> (u64, u64) {
+        let (mut nodes, mut sum) = (0, 0);
+        let mut pending = vec![tree];
+        while let Some(node) = pending.pop() {
+            nodes += 1;
+            sum += node.value;
+            pending.extend(&node.children);
+        }
+        (nodes, sum)
+    }
+
+    #[test]
+    fn build_nary_creates_exactly_count_nodes() {
+        for fanout in [1, 2, 4, 16] {
+            for count in [1, 2, 5, 17, 100, 1000] {
+                let tree = build_nary(0, count, fanout);
+                assert_eq!(
+                    nary_nodes(&tree),
+                    (count, count * (count - 1) / 2),
+                    "count {} fanout {}",
+                    count,
+                    fanout
+                );
+                assert!(tree.children.len() as u64 <= fanout);
+            }
+        }
+    }
+
+    #[test]
+    fn deep_trees_drop_without_recursion() {
+        drop(make_degenerate_binary(1_000_000));
+        drop(build_nary(0, 1_000_000, 1));
+    }
+}
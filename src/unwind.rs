@@ -0,0 +1,299 @@
+use crate::output::{self, Format};
+use crate::scenarios::{measure, Measurement, Scenario, ScenarioResult};
+use crate::{make_boxed_fact_u64, make_boxed_string, BoxedFact, BoxedString};
+use stacker::remaining_stack;
+use std::cell::Cell;
+use std::hint::black_box;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::sync::Once;
+use std::time::Instant;
+
+// IMPORTANT: Every family here panics at the leaf and catches the panic at the top with
+// catch_unwind. That answers three questions per depth: how much stack the panic machinery
+// needs below the deepest frame, how long unwinding N frames takes, and whether the catch
+// succeeds at all. With panic=abort nothing can be caught - those builds skip the family.
+
+// IMPORTANT: A recursive call in tail position is turned into a loop in release builds and
+// there would be a single frame to unwind. Each level below reads its input again AFTER
+// the call, like real code that still has work to do, so every frame stays on the stack.
+
+// Payload of the leaf panic, recognised by the quiet hook below
+struct LeafPanic;
+
+// Bytes painted below the leaf frame, and the pattern they are painted with
+const PAINT_BYTES: usize = 64 * 1024;
+const PAINT: u8 = 0xA5;
+
+thread_local! {
+    // The time at the moment the hook saw the leaf panic
+    static PANIC_SITE: Cell<Option<Instant>> = const { Cell::new(None) };
+    // Lowest address and length of the region painted below the leaf frame
+    static PAINTED: Cell<Option<(usize, usize)>> = const { Cell::new(None) };
+}
+
+// CRITICAL: The default hook prints a message (and may capture a backtrace) for every run.
+// This one stays silent for LeafPanic and records when the panic happened;
+// any other panic still goes to the previous hook.
+fn install_hook() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if info.payload().is::<LeafPanic>() {
+                PANIC_SITE.with(|site| site.set(Some(Instant::now())));
+            } else {
+                previous(info);
+            }
+        }));
+    });
+}
+
+// KEY: Called by the leaf right before it panics. The array sits directly below the leaf
+// frame and still holds the pattern once this returns, so whatever the hook, the panic
+// machinery and _Unwind_RaiseException write there afterwards shows up in unwinder_depth.
+// Leaves too close to the guard page skip painting and report no figure.
+#[inline(never)]
+fn paint_below() {
+    if remaining_stack().is_none_or(|rem| rem < 2 * PAINT_BYTES) {
+        return;
+    }
+    let mut region = [PAINT; PAINT_BYTES];
+    let region = black_box(&mut region);
+    PAINTED.with(|painted| painted.set(Some((region.as_ptr() as usize, PAINT_BYTES))));
+}
+
+// CRITICAL: Runs after catch_unwind has returned, far above the painted region. Stack grows
+// down, so bytes still holding the pattern from the low end up were never reached; the rest
+// is the high-water mark. The volatile reads go to memory no live frame owns any more, but
+// it is still mapped stack of this thread.
+fn unwinder_depth() -> Option<usize> {
+    let (low, len) = PAINTED.with(Cell::take)?;
+    let untouched = (0..len)
+        .take_while(|&i| unsafe { ptr::read_volatile((low + i) as *const u8) } == PAINT)
+        .count();
+    Some(len - untouched)
+}
+
+// === panic ที่ leaf: fn ธรรมดา ===
+pub fn panic_simple_tracked(n: u64, stack_info: &mut Vec<usize>) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    if n == 0 {
+        paint_below();
+        panic::panic_any(LeafPanic);
+    }
+    panic_simple_tracked(n - 1, stack_info);
+    black_box(n);
+}
+
+// === panic ที่ leaf: Box recursion ===
+pub fn panic_boxed_tracked<T>(f: &BoxedFact<T>, stack_info: &mut Vec<usize>) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    match f {
+        BoxedFact::Next(_, next) => {
+            panic_boxed_tracked(next, stack_info);
+            black_box(f);
+        }
+        BoxedFact::Done(_) => {
+            paint_below();
+            panic::panic_any(LeafPanic)
+        }
+    }
+}
+
+// === panic ที่ leaf: ต่อ string (pure) ===
+pub fn panic_string_tracked(n: u64, stack_info: &mut Vec<usize>, s: &mut String) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    s.push_str(&format!("{}-", n));
+    if n == 0 {
+        paint_below();
+        panic::panic_any(LeafPanic);
+    }
+    panic_string_tracked(n - 1, stack_info, s);
+    black_box(n);
+}
+
+// === panic ที่ leaf: ต่อ string (boxed) ===
+pub fn panic_boxed_string_tracked(f: &BoxedString, stack_info: &mut Vec<usize>, out: &mut String) {
+    if let Some(rem) = remaining_stack() {
+        stack_info.push(rem);
+    }
+    match f {
+        BoxedString::Next(s, next) => {
+            out.push_str(s);
+            panic_boxed_string_tracked(next, stack_info, out);
+            black_box(f);
+        }
+        BoxedString::Done(s) => {
+            out.push_str(s);
+            paint_below();
+            panic::panic_any(LeafPanic);
+        }
+    }
+}
+
+pub struct UnwindRun {
+    pub measurement: Measurement,
+    pub caught: bool,
+    pub unwinder_bytes: Option<usize>, // High-water mark below the leaf frame while unwinding
+    pub unwind_ns: Option<f64>,        // Hook -> catch_unwind returned
+}
+
+// IMPORTANT: unwinder_bytes is measured by painting, not sampled from the hook - it covers
+// the hook, the personality routine and _Unwind_RaiseException. A value of PAINT_BYTES means
+// the whole region was used and the figure is only a lower bound.
+fn caught_at_top<T>(
+    n: u64,
+    build: impl FnOnce() -> T,
+    eval: impl FnOnce(&mut T, &mut Vec<usize>),
+) -> UnwindRun {
+    install_hook();
+    PANIC_SITE.with(|site| site.set(None));
+    PAINTED.with(|painted| painted.set(None));
+    let mut caught = false;
+    let mut unwound_at = None;
+    let mut unwinder_bytes = None;
+    let measurement = measure(n, build, |value, stack_info| {
+        caught = panic::catch_unwind(AssertUnwindSafe(|| eval(value, stack_info))).is_err();
+        unwound_at = Some(Instant::now());
+        unwinder_bytes = unwinder_depth();
+    });
+    let site = PANIC_SITE.with(Cell::take);
+    UnwindRun {
+        caught,
+        unwinder_bytes,
+        unwind_ns: site
+            .zip(unwound_at)
+            .map(|(at, done)| done.duration_since(at).as_nanos() as f64),
+        measurement,
+    }
+}
+
+fn unwind_simple(n: u64) -> UnwindRun {
+    caught_at_top(
+        n,
+        || n,
+        |n, stack_info| panic_simple_tracked(*n, stack_info),
+    )
+}
+
+fn unwind_boxed(n: u64) -> UnwindRun {
+    caught_at_top(
+        n,
+        || make_boxed_fact_u64(n),
+        |fact, stack_info| panic_boxed_tracked(fact, stack_info),
+    )
+}
+
+fn unwind_string_pure(n: u64) -> UnwindRun {
+    caught_at_top(
+        n,
+        || String::with_capacity((n as usize) * 4),
+        |s, stack_info| panic_string_tracked(n, stack_info, s),
+    )
+}
+
+fn unwind_string_boxed(n: u64) -> UnwindRun {
+    caught_at_top(
+        n,
+        || {
+            (
+                make_boxed_string(n),
+                String::with_capacity((n as usize) * 4),
+            )
+        },
+        |(tree, out), stack_info| panic_boxed_string_tracked(tree, stack_info, out),
+    )
+}
+
+type Family = fn(u64) -> UnwindRun;
+
+pub const FAMILIES: &[(&str, Family)] = &[
+    ("unwind-simple(u64)", unwind_simple),
+    ("unwind-boxed(u64)", unwind_boxed),
+    ("unwind-string(pure)", unwind_string_pure),
+    ("unwind-string(boxed)", unwind_string_boxed),
+];
+
+pub const SCENARIOS: &[Scenario] = &[
+    Scenario {
+        name: "unwind-simple(u64)",
+        run: |n| unwind_simple(n).measurement,
+        iterative: false,
+        requires_unwind: true,
+    },
+    Scenario {
+        name: "unwind-boxed(u64)",
+        run: |n| unwind_boxed(n).measurement,
+        iterative: false,
+        requires_unwind: true,
+    },
+    Scenario {
+        name: "unwind-string(pure)",
+        run: |n| unwind_string_pure(n).measurement,
+        iterative: false,
+        requires_unwind: true,
+    },
+    Scenario {
+        name: "unwind-string(boxed)",
+        run: |n| unwind_string_boxed(n).measurement,
+        iterative: false,
+        requires_unwind: true,
+    },
+];
+
+// === ตาราง unwind ===
+// CRITICAL: The scenarios only carry the generic numbers, so the table re-runs each family
+// that fit on the stack (skipped/failed rows are never re-run) to read the unwind figures
+pub fn render_table(format: Format, results: &[&ScenarioResult], stack_size: usize) -> String {
+    let rows: Vec<Vec<String>> = FAMILIES
+        .iter()
+        .filter_map(|&(name, family)| {
+            let result = results.iter().copied().find(|r| r.name == name)?;
+            let run = result.report.as_ref().and_then(|_| {
+                let depth = result.depth;
+                std::thread::Builder::new()
+                    .stack_size(stack_size)
+                    .spawn(move || family(depth))
+                    .ok()
+                    .and_then(|handle| handle.join().ok())
+            });
+            let levels = result.report.as_ref().map_or(1, |r| r.levels) as f64;
+            let caught = match (&result.skipped, &run) {
+                (Some(_), _) => "skipped".to_string(),
+                (None, Some(run)) if run.caught => "yes".to_string(),
+                _ => "no".to_string(),
+            };
+            Some(vec![
+                name.to_string(),
+                output::per_level_cell(Some(result)),
+                run.as_ref()
+                    .and_then(|r| r.unwinder_bytes)
+                    .map_or("-".to_string(), |b| b.to_string()),
+                run.as_ref()
+                    .and_then(|r| r.unwind_ns)
+                    .map_or("-".to_string(), |ns| format!("{:.1}", ns / levels)),
+                caught,
+            ])
+        })
+        .collect();
+    let depth = results.first().map_or(0, |r| r.depth);
+    output::render_grid(
+        format,
+        &format!("Panic at the leaf, caught at the top (depth {})", depth),
+        &[
+            "Scenario",
+            "B/lvl",
+            "Unwinder stack (bytes)",
+            "Unwind ns/lvl",
+            "catch_unwind",
+        ],
+        &rows,
+    )
+}
@@ -0,0 +1,120 @@
+use crate::analyze_stack;
+use crate::metadata::Metadata;
+use crate::output::{self, Format};
+use crate::scenarios::{self, Measurement, RunConfig, Scenario, ScenarioResult};
+use std::cell::Cell;
+
+// IMPORTANT: Every other mode recurses on a fresh thread with --stack-size bytes. Services
+// recurse on runtime worker threads instead - 2 MiB by default, part of it already used by
+// the runtime's own frames. A context runs each scenario on such a thread, and the stack
+// still available at the first level gives the max depth that context can take.
+
+pub const DEFAULT_WORKER_STACK: usize = 2 * 1024 * 1024; // tokio's and rayon's default
+
+// Owned counterpart of scenarios::Runner
+pub type BoxedRunner<'a> = Box<dyn Fn(&Scenario, u64) -> Option<Measurement> + 'a>;
+
+pub struct WorkerContext<'a> {
+    pub label: &'static str,
+    pub runner: BoxedRunner<'a>,
+}
+
+// What the context left for the recursion, next to the usual ScenarioResult
+pub struct WorkerStack {
+    pub context: &'static str,
+    pub scenario: &'static str,
+    pub available: Option<usize>, // remaining_stack() at the first level of the last run
+    pub per_level: Option<f64>,   // Last run, so a skipped depth still has its probe's figure
+    pub tail_call_eliminated: bool,
+}
+
+pub fn run_on(
+    contexts: &[WorkerContext],
+    registry: &[&'static Scenario],
+    depths: &[u64],
+    config: &RunConfig,
+    metadata: &Metadata,
+) -> Vec<(ScenarioResult, WorkerStack)> {
+    let mut rows = Vec::new();
+    for scenario in registry {
+        for &depth in depths {
+            for context in contexts {
+                let last = Cell::new(None);
+                let recording = |scenario: &Scenario, depth: u64| {
+                    let m = (context.runner)(scenario, depth)?;
+                    if let (Some(report), Some(&available)) =
+                        (analyze_stack(&m.stack_info), m.stack_info.first())
+                    {
+                        last.set(Some((
+                            available,
+                            report.per_level,
+                            report.tail_call_eliminated,
+                        )));
+                    }
+                    Some(m)
+                };
+                let mut result =
+                    scenarios::run_scenario_on(scenario, depth, config, metadata, &recording);
+                result.name = format!("{} @{}", scenario.name, context.label);
+                let last = last.get();
+                rows.push((
+                    result,
+                    WorkerStack {
+                        context: context.label,
+                        scenario: scenario.name,
+                        available: last.map(|(available, _, _)| available),
+                        per_level: last.map(|(_, per_level, _)| per_level),
+                        tail_call_eliminated: last.is_some_and(|(_, _, tce)| tce),
+                    },
+                ));
+            }
+        }
+    }
+    rows
+}
+
+pub fn max_depth_cell(stack: &WorkerStack) -> String {
+    match (stack.available, stack.per_level) {
+        // The eval runs as a loop; the build and drop around it are bounded by heap, not stack
+        _ if stack.tail_call_eliminated => "eval unbounded".to_string(),
+        (Some(available), Some(per_level)) if per_level > 0.0 => {
+            format!("~{}", (available as f64 / per_level) as u64)
+        }
+        _ => "-".to_string(),
+    }
+}
+
+// === ตาราง max safe depth ต่อ context ===
+pub fn render_table(
+    format: Format,
+    title: &str,
+    rows: &[(&ScenarioResult, &WorkerStack)],
+) -> String {
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|&(result, stack)| {
+            vec![
+                stack.scenario.to_string(),
+                stack.context.to_string(),
+                output::per_level_cell(Some(result)),
+                stack
+                    .available
+                    .map_or("-".to_string(), |available| available.to_string()),
+                max_depth_cell(stack),
+            ]
+        })
+        .collect();
+    let depth = rows.first().map_or(0, |(r, _)| r.depth);
+    output::render_grid(
+        format,
+        &format!("{} (depth {})", title, depth),
+        &[
+            "Scenario",
+            "Context",
+            "B/lvl",
+            "Stack available",
+            "Max depth (est.)",
+        ],
+        &cells,
+    )
+}